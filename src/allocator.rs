@@ -1,4 +1,5 @@
 use crate::raw_arena::RawArena;
+use crate::{ChunkAllocator, Global};
 
 /// A bump allocator that allocates objects into an [`Arena`](crate::Arena).
 ///
@@ -18,39 +19,92 @@ use crate::raw_arena::RawArena;
 /// assert_eq!(*my_num, 0xABCDi32);
 /// ```
 #[derive(Debug)]
-pub struct Allocator<'a> {
-    arena: &'a mut RawArena,
+pub struct Allocator<'a, A: ChunkAllocator = Global> {
+    arena: &'a mut RawArena<A>,
 }
 
-impl<'a> Allocator<'a> {
+impl<'a, A: ChunkAllocator> Allocator<'a, A> {
     /// Creates a new [`Allocator`] to allocate into the specified arena.
     ///
     /// See [`Arena::allocator()`](crate::Arena::allocator) for information regarding the
     /// usage of a mutable reference.
-    pub(crate) fn with_arena(arena: &'a mut RawArena) -> Self {
+    pub(crate) fn with_arena(arena: &'a mut RawArena<A>) -> Self {
         Self { arena }
     }
-}
 
-// Safety: Allocator 'me lives as long as the arena 'a
-unsafe impl<'me, 'a: 'me> crate::Bump<'me, 'a> for Allocator<'a> {
-    #[inline(always)]
-    fn alloc_with_layout(&'me self, layout: core::alloc::Layout) -> core::ptr::NonNull<u8> {
-        self.arena.alloc_with_layout(layout)
+    /// Returns the number of bytes that can still be used for new chunks before the arena's
+    /// memory limit is reached, or [`None`] if there is no limit.
+    ///
+    /// See [`Arena::remaining_memory`](crate::Arena::remaining_memory) for more information.
+    pub fn remaining_memory(&self) -> Option<usize> {
+        self.arena.memory().remaining()
     }
 
-    #[inline(always)]
-    fn with_frame<T, F: FnOnce(&mut crate::Frame) -> T>(&'me mut self, f: F) -> T {
-        crate::Frame::in_arena(self.arena, f)
+    /// Returns a snapshot of the arena's memory usage.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Returns an iterator describing each of the arena's chunks, from the first chunk to the
+    /// last.
+    ///
+    /// See [`ChunkInfo`](crate::ChunkInfo) for more information.
+    pub fn chunks(&self) -> crate::ArenaChunks<'_> {
+        self.arena.chunk_infos()
+    }
+
+    /// Returns a [`Checkpoint`](crate::Checkpoint) that frees the objects allocated with it once
+    /// it is dropped or rolled back.
+    pub fn checkpoint(&mut self) -> crate::Checkpoint<'_, A> {
+        crate::Checkpoint::in_arena(self.arena)
+    }
+
+    /// Moves the `value` into the arena, returning a [`Handle`](crate::Handle) to it that can
+    /// outlive this [`Allocator`].
+    ///
+    /// Like [`alloc_with_drop`](crate::Bump::alloc_with_drop), the `value` is dropped when the
+    /// arena is reset or dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated.
+    pub fn alloc_handle<T: Send + 'static>(&self, value: T) -> crate::Handle<T> {
+        self.try_alloc_handle(value).unwrap()
     }
 
+    /// Attempts to move the `value` into the arena, returning a [`Handle`](crate::Handle) to it
+    /// that can outlive this [`Allocator`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`](crate::AllocError) if any calls to an underlying memory
+    /// allocator fail, in which case the `value` is dropped.
+    pub fn try_alloc_handle<T: Send + 'static>(
+        &self,
+        value: T,
+    ) -> Result<crate::Handle<T>, crate::AllocError> {
+        let pointer = crate::Bump::try_alloc_with_drop(self, value)?;
+        let (arena, generation) = self.arena.register_handle();
+        Ok(crate::Handle::new(pointer.into(), arena, generation))
+    }
+}
+
+impl<A: ChunkAllocator> crate::bump::private::Sealed for Allocator<'_, A> {
     #[inline(always)]
-    unsafe fn alloc_try_with_layout<R, F>(&'me self, layout: core::alloc::Layout, f: F) -> R
-    where
-        R: crate::private::Try,
-        F: FnOnce(core::ptr::NonNull<u8>) -> R,
-    {
-        // Safety: ensured by caller
-        unsafe { self.arena.alloc_try_with_layout(layout, f) }
+    fn current_finger(&self) -> Option<&core::sync::atomic::AtomicPtr<u8>> {
+        self.arena.current_finger()
     }
 }
+
+// Safety: Allocator 'me lives as long as the arena 'a
+unsafe impl<'me, 'a: 'me, A: ChunkAllocator> crate::Bump<'me, 'a> for Allocator<'a, A> {
+    type ChunkAllocator = A;
+
+    crate::bump::forward_bump_methods!('me, 'a);
+}
+
+#[cfg(feature = "allocator-api2")]
+// Safety: allocations live for the lifetime of the arena, which outlives the reference
+crate::allocator_api::impl_allocator!([A: ChunkAllocator] Allocator<'_, A>);
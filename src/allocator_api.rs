@@ -0,0 +1,201 @@
+//! Helpers for implementing [`allocator_api2::alloc::Allocator`] for references to bump
+//! allocators.
+
+use crate::raw_arena::RawArena;
+use crate::ChunkAllocator;
+use allocator_api2::alloc::AllocError;
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+#[inline(always)]
+fn block(pointer: NonNull<u8>, length: usize) -> NonNull<[u8]> {
+    // Safety: pointer is not null
+    unsafe {
+        NonNull::new_unchecked(core::ptr::slice_from_raw_parts_mut(
+            pointer.as_ptr(),
+            length,
+        ))
+    }
+}
+
+#[inline(always)]
+pub(crate) fn allocate<A: ChunkAllocator>(
+    arena: &RawArena<A>,
+    layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    match arena.try_alloc_with_layout(layout) {
+        Ok(allocation) => Ok(block(allocation, layout.size())),
+        Err(_) => Err(AllocError),
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn deallocate<A: ChunkAllocator>(
+    arena: &RawArena<A>,
+    pointer: NonNull<u8>,
+    layout: Layout,
+) {
+    // Safety: ensured by caller
+    unsafe {
+        arena.dealloc_last(pointer, layout);
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn grow<A: ChunkAllocator>(
+    arena: &RawArena<A>,
+    pointer: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    // Safety: ensured by caller
+    match unsafe { arena.grow(pointer, old_layout, new_layout) } {
+        Ok(allocation) => Ok(block(allocation, new_layout.size())),
+        Err(_) => Err(AllocError),
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn grow_zeroed<A: ChunkAllocator>(
+    arena: &RawArena<A>,
+    pointer: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    // Safety: ensured by caller
+    let allocation = unsafe { grow(arena, pointer, old_layout, new_layout)? };
+
+    // Safety: new allocation is at least as large as the old one
+    unsafe {
+        allocation
+            .cast::<u8>()
+            .as_ptr()
+            .add(old_layout.size())
+            .write_bytes(0, new_layout.size() - old_layout.size());
+    }
+
+    Ok(allocation)
+}
+
+#[inline(always)]
+pub(crate) unsafe fn shrink<A: ChunkAllocator>(
+    arena: &RawArena<A>,
+    pointer: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    // Safety: ensured by caller
+    match unsafe { arena.shrink(pointer, old_layout, new_layout) } {
+        Ok(allocation) => Ok(block(allocation, new_layout.size())),
+        Err(_) => Err(AllocError),
+    }
+}
+
+/// Implements [`allocator_api2::alloc::Allocator`] for references to a bump allocator type with a
+/// `self.arena` field that dereferences to a [`RawArena`].
+///
+/// The caller is responsible for ensuring that allocations made through the arena live for at
+/// least as long as the reference.
+macro_rules! impl_allocator {
+    ([$($generics:tt)*] $ty:ty) => {
+        // Safety: ensured by caller
+        unsafe impl<$($generics)*> allocator_api2::alloc::Allocator for &$ty {
+            #[inline(always)]
+            fn allocate(
+                &self,
+                layout: core::alloc::Layout,
+            ) -> Result<core::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+                crate::allocator_api::allocate(&*self.arena, layout)
+            }
+
+            #[inline(always)]
+            unsafe fn deallocate(&self, pointer: core::ptr::NonNull<u8>, layout: core::alloc::Layout) {
+                // Safety: ensured by caller
+                unsafe { crate::allocator_api::deallocate(&*self.arena, pointer, layout) }
+            }
+
+            #[inline(always)]
+            unsafe fn grow(
+                &self,
+                pointer: core::ptr::NonNull<u8>,
+                old_layout: core::alloc::Layout,
+                new_layout: core::alloc::Layout,
+            ) -> Result<core::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+                // Safety: ensured by caller
+                unsafe { crate::allocator_api::grow(&*self.arena, pointer, old_layout, new_layout) }
+            }
+
+            #[inline(always)]
+            unsafe fn grow_zeroed(
+                &self,
+                pointer: core::ptr::NonNull<u8>,
+                old_layout: core::alloc::Layout,
+                new_layout: core::alloc::Layout,
+            ) -> Result<core::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+                // Safety: ensured by caller
+                unsafe {
+                    crate::allocator_api::grow_zeroed(&*self.arena, pointer, old_layout, new_layout)
+                }
+            }
+
+            #[inline(always)]
+            unsafe fn shrink(
+                &self,
+                pointer: core::ptr::NonNull<u8>,
+                old_layout: core::alloc::Layout,
+                new_layout: core::alloc::Layout,
+            ) -> Result<core::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+                // Safety: ensured by caller
+                unsafe { crate::allocator_api::shrink(&*self.arena, pointer, old_layout, new_layout) }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_allocator;
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+    use allocator_api2::vec::Vec;
+
+    #[test]
+    fn vec_grows_in_place() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut items = Vec::<u32, _>::with_capacity_in(4, &allocator);
+        let end = items.as_ptr() as usize + items.capacity() * 4;
+
+        for i in 0..128 {
+            items.push(i);
+        }
+
+        assert_eq!(items.as_ptr() as usize + items.capacity() * 4, end);
+        assert!(items.iter().copied().eq(0..128));
+    }
+
+    #[test]
+    fn deallocate_reclaims_last_allocation() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        allocator.with_frame(|frame| {
+            let first = allocator_api2::boxed::Box::new_in(5u64, &*frame);
+            let address = &*first as *const u64;
+            core::mem::drop(first);
+
+            let second = allocator_api2::boxed::Box::new_in(6u64, &*frame);
+            assert_eq!(&*second as *const u64, address);
+        });
+    }
+
+    #[test]
+    fn shrink_keeps_contents() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut items = Vec::<u8, _>::with_capacity_in(64, &allocator);
+        items.extend_from_slice(b"hello");
+        items.shrink_to_fit();
+        assert_eq!(items.as_slice(), b"hello");
+        assert_eq!(items.capacity(), 5);
+    }
+}
@@ -1,41 +1,157 @@
+use crate::{ChunkAllocator, Global};
+
 /// An arena, owns regions of memory that objects are bump allocated into.
 ///
 /// To allocate objects into the arena, see the [`allocator()`](Arena::allocator) method.
+///
+/// The memory for the arena's chunks is obtained from a [`ChunkAllocator`], which defaults to
+/// the [`Global`] allocator.
 #[derive(Debug)]
-pub struct Arena {
-    arena: crate::raw_arena::RawArena,
+pub struct Arena<A: ChunkAllocator = Global> {
+    arena: crate::raw_arena::RawArena<A>,
 }
 
 impl Arena {
     /// Creates an empty arena.
     pub fn new() -> Self {
-        Self::with_capacity(0)
+        Self::new_in(Global)
     }
 
     /// Creates an arena, allocating a new chunk to contain at least `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk could not be allocated, see [`Arena::try_with_capacity`] for a fallible
+    /// version.
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    /// Attempts to create an arena, allocating a new chunk to contain at least `capacity` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`](crate::AllocError) if the chunk could not be allocated.
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, crate::AllocError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+}
+
+impl<A: ChunkAllocator> Arena<A> {
+    pub(crate) fn with_raw_arena(arena: crate::raw_arena::RawArena<A>) -> Self {
+        Self { arena }
+    }
+
+    /// Creates an empty arena whose chunks are allocated with the given [`ChunkAllocator`].
+    ///
+    /// To configure the size of the arena's chunks, see [`ArenaBuilder`](crate::ArenaBuilder).
+    pub fn new_in(allocator: A) -> Self {
+        Self::with_raw_arena(crate::raw_arena::RawArena::new_in(allocator))
+    }
+
+    /// Creates an arena whose chunks are allocated with the given [`ChunkAllocator`], allocating
+    /// a new chunk to contain at least `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk could not be allocated.
+    pub fn with_capacity_in(capacity: usize, allocator: A) -> Self {
         Self {
-            arena: crate::raw_arena::RawArena::with_capacity(capacity),
+            arena: crate::raw_arena::RawArena::with_capacity_in(capacity, allocator),
         }
     }
 
+    /// Attempts to create an arena whose chunks are allocated with the given [`ChunkAllocator`],
+    /// allocating a new chunk to contain at least `capacity` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`](crate::AllocError) if the chunk could not be allocated.
+    pub fn try_with_capacity_in(capacity: usize, allocator: A) -> Result<Self, crate::AllocError> {
+        Ok(Self {
+            arena: crate::raw_arena::RawArena::try_with_capacity_in(capacity, allocator)?,
+        })
+    }
+
+    /// Returns a reference to the [`ChunkAllocator`] used to allocate the arena's chunks.
+    pub fn chunk_allocator(&self) -> &A {
+        self.arena.chunk_allocator()
+    }
+
+    /// Returns the maximum number of bytes that the arena's chunks can occupy, or [`None`] if
+    /// there is no limit.
+    ///
+    /// See [`ArenaBuilder::memory_limit`](crate::ArenaBuilder::memory_limit) for more
+    /// information.
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.arena.memory().limit()
+    }
+
+    /// Returns the number of bytes that can still be used for new chunks before the memory limit
+    /// is reached, or [`None`] if there is no limit.
+    pub fn remaining_memory(&self) -> Option<usize> {
+        self.arena.memory().remaining()
+    }
+
+    /// Returns a snapshot of the arena's memory usage.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Returns an iterator describing each of the arena's chunks, from the first chunk to the
+    /// last.
+    ///
+    /// See [`ChunkInfo`](crate::ChunkInfo) for more information.
+    pub fn chunks(&self) -> crate::ArenaChunks<'_> {
+        self.arena.chunk_infos()
+    }
+
     /// Returns an [`Allocator`] used to allocate objects into the arena.
     ///
     /// Note that the usage of `&mut self` ensures that **only** the returned [`Allocator`]
     /// can allocate objects into the arena.
     ///
     /// [`Allocator`]: crate::Allocator
-    pub fn allocator(&mut self) -> crate::Allocator<'_> {
+    pub fn allocator(&mut self) -> crate::Allocator<'_, A> {
         crate::Allocator::with_arena(&mut self.arena)
     }
 
     /// Resets the arena by moving the bump pointer back to the first chunk.
     ///
-    /// This allows reusing of memory allocated by the arena.
+    /// This allows reusing of memory allocated by the arena. All chunks are kept, see
+    /// [`reset_and_shrink`](Arena::reset_and_shrink) to free some of them.
+    ///
+    /// Objects allocated with [`alloc_with_drop`](crate::Bump::alloc_with_drop) are dropped, in
+    /// the reverse of the order that they were allocated in. The arena's
+    /// [`generation`](Arena::generation) is incremented, so that any [`Handle`](crate::Handle)s
+    /// to its objects become stale.
     pub fn reset(&mut self) {
         // Safety: &mut self ensures there are no extant references that can become dangling
         unsafe { self.arena.reset() }
     }
+
+    /// Resets the arena, and frees the chunks that are not kept by the given `policy`.
+    ///
+    /// See [`RetentionPolicy`](crate::RetentionPolicy) for more information.
+    pub fn reset_and_shrink(&mut self, policy: crate::RetentionPolicy) {
+        // Safety: &mut self ensures there are no extant references that can become dangling
+        unsafe { self.arena.reset_and_shrink(policy) }
+    }
+
+    /// Returns the number of times that the arena was reset, which makes every
+    /// [`Handle`](crate::Handle) created before then stale.
+    ///
+    /// Handles can only be created by the arena's [`Allocator`](crate::Allocator) and not within
+    /// a [`Frame`](crate::Frame), so rolling back a frame does not change the generation.
+    pub fn generation(&self) -> u64 {
+        self.arena.generation()
+    }
+
+    pub(crate) fn raw_arena(&self) -> &crate::raw_arena::RawArena<A> {
+        &self.arena
+    }
 }
 
 impl core::default::Default for Arena {
@@ -45,7 +161,7 @@ impl core::default::Default for Arena {
 }
 
 // Safety: Safe to send across threads, borrow checker ensures there are no extant Allocators
-unsafe impl Send for Arena {}
+unsafe impl<A: ChunkAllocator + Send> Send for Arena<A> {}
 
 #[cfg(any(test, miri))]
 mod tests {
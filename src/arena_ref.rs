@@ -0,0 +1,158 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Contains an object that is initialized after references to it are handed out.
+pub(crate) struct Slot<T> {
+    initialized: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Slot<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            initialized: AtomicBool::new(false),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Calls the closure with an [`ArenaRef`] to the slot, and initializes the slot with the
+    /// returned value.
+    pub(crate) fn initialize<'a, F: FnOnce(ArenaRef<'a, T>) -> T>(&'a mut self, f: F) -> &'a T {
+        let this = ArenaRef {
+            slot: NonNull::from(&*self),
+            _marker: PhantomData,
+        };
+
+        let value = f(this);
+        let slot = this.slot();
+
+        // Safety: the slot is only read once it is initialized, and is only initialized once
+        unsafe { slot.value.get().write(MaybeUninit::new(value)) };
+        slot.initialized.store(true, Ordering::Release);
+
+        // Safety: slot was initialized above
+        unsafe { &*slot.value.get().cast::<T>() }
+    }
+}
+
+/// A reference to an object in an arena that may not have been initialized yet.
+///
+/// An [`ArenaRef`] is provided to the closure passed to
+/// [`Bump::alloc_cyclic`](crate::Bump::alloc_cyclic), allowing an object to store its own
+/// address. Reading the object through the [`ArenaRef`] is only possible once the closure returns
+/// and the object is initialized.
+///
+/// If the closure panics, the object is never initialized, so [`get`](ArenaRef::get) always
+/// returns [`None`].
+pub struct ArenaRef<'a, T> {
+    slot: NonNull<Slot<T>>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> ArenaRef<'a, T> {
+    #[inline(always)]
+    fn slot(self) -> &'a Slot<T> {
+        // Safety: slot is valid for 'a
+        unsafe { self.slot.as_ref() }
+    }
+
+    /// Returns `true` if the object has been initialized.
+    #[inline(always)]
+    pub fn is_initialized(self) -> bool {
+        self.slot().initialized.load(Ordering::Acquire)
+    }
+
+    /// Returns a reference to the object, or [`None`] if it is still being initialized.
+    #[inline(always)]
+    pub fn get(self) -> Option<&'a T> {
+        if self.is_initialized() {
+            // Safety: object is initialized, and is no longer mutated
+            Some(unsafe { &*self.slot().value.get().cast::<T>() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a pointer to the object, which is valid for reads once it is initialized.
+    #[inline(always)]
+    pub fn as_ptr(self) -> *const T {
+        self.slot().value.get().cast::<T>()
+    }
+
+    /// Returns `true` if both [`ArenaRef`]s refer to the same object.
+    #[inline(always)]
+    pub fn ptr_eq(self, other: Self) -> bool {
+        self.slot == other.slot
+    }
+}
+
+impl<T> Clone for ArenaRef<'_, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaRef<'_, T> {}
+
+impl<T> core::ops::Deref for ArenaRef<'_, T> {
+    type Target = T;
+
+    /// Dereferences the object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is still being initialized, see [`ArenaRef::get`] for a fallible
+    /// version.
+    fn deref(&self) -> &T {
+        self.get().expect("object is still being initialized")
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ArenaRef<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("ArenaRef").field(value).finish(),
+            None => f.write_str("ArenaRef(<uninitialized>)"),
+        }
+    }
+}
+
+// Safety: ArenaRef only provides shared access to the object, and initialization is synchronized
+unsafe impl<T: Sync> Send for ArenaRef<'_, T> {}
+
+// Safety: see above
+unsafe impl<T: Sync> Sync for ArenaRef<'_, T> {}
+
+#[cfg(all(any(test, miri), feature = "std", panic = "unwind"))]
+mod tests {
+    use super::ArenaRef;
+    use crate::prelude::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn escaped_reference_is_never_initialized_after_panic() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let escaped = Cell::new(None);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            allocator.alloc_cyclic(|this: ArenaRef<u32>| {
+                escaped.set(Some(this));
+                panic!("initialization failed")
+            });
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(escaped.get().and_then(ArenaRef::get), None);
+
+        let value = allocator.alloc_cyclic(|this: ArenaRef<u32>| {
+            escaped.set(Some(this));
+            5
+        });
+        assert_eq!(escaped.get().and_then(ArenaRef::get), Some(&5));
+        assert_eq!(escaped.get().unwrap().as_ptr(), value as *const u32);
+    }
+}
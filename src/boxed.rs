@@ -31,14 +31,65 @@
 use crate::Bump;
 use core::mem::MaybeUninit;
 use core::ops::DerefMut;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::sync::atomic::AtomicPtr;
 
 /// Provides ownership for a value stored in an [`Arena`](crate::Arena), and allows running its
 /// [`Drop`](core::ops::Drop) implementation.
 ///
+/// If the value is still the most recent allocation when the [`Box<'b, T>`](self::Box) is
+/// dropped, then its memory is also reclaimed, see [`Bump::dealloc_last`] for more information.
+/// This also works for boxes that were sent to and dropped on another thread.
+///
+/// # Pinning
+///
+/// A value pinned with [`Box::pin`] is dropped when the [`Box<'b, T>`](self::Box) is dropped,
+/// before its memory can be reclaimed or reused. However, unlike a [`Box`](alloc::boxed::Box)
+/// on the heap, leaking a [`Box<'b, T>`](self::Box) with [`mem::forget`](core::mem::forget)
+/// does not leak its memory forever, since it is reused once the arena is reset, once the frame
+/// it was allocated in ends, or once the arena is dropped. This would break the drop guarantee of
+/// [`Pin`], so [`Box::pin`] is `unsafe`, see [`Bump::alloc_pinned`] for a safe alternative.
+///
 /// See the [module level documentation](crate::boxed) for more information.
-#[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Box<'b, T: ?Sized> {
     value: &'b mut T,
+    /// Finger of the chunk that was current when the value was allocated, used to reclaim its
+    /// memory.
+    finger: Option<&'b AtomicPtr<u8>>,
+}
+
+impl<'b, T: ?Sized> Box<'b, T> {
+    /// Takes ownership of a `value` allocated by the `allocator`.
+    #[inline(always)]
+    pub(crate) fn with_finger<'a, A: Bump<'a, 'b> + ?Sized>(
+        value: &'b mut T,
+        allocator: &'a A,
+    ) -> Self {
+        Self {
+            finger: Self::finger_of(value, allocator),
+            value,
+        }
+    }
+
+    /// Returns the finger used to reclaim the memory of a `value` that was just allocated by the
+    /// `allocator`.
+    #[inline(always)]
+    fn finger_of<'a, A: Bump<'a, 'b> + ?Sized>(
+        value: &T,
+        allocator: &'a A,
+    ) -> Option<&'b AtomicPtr<u8>> {
+        if core::mem::size_of_val(value) == 0 {
+            // Nothing to reclaim
+            return None;
+        }
+
+        allocator.current_finger().map(|finger| {
+            // Safety: chunks are not freed while objects allocated in them are still alive,
+            // so the finger lives as long as the value
+            unsafe { &*(finger as *const AtomicPtr<u8>) }
+        })
+    }
 }
 
 impl<'b, T> Box<'b, T> {
@@ -55,9 +106,57 @@ impl<'b, T> Box<'b, T> {
     /// let six = Box::new(6, &allocator);
     /// ```
     pub fn new<'a, A: Bump<'a, 'b>>(value: T, allocator: &'a A) -> Self {
-        Self {
-            value: allocator.alloc(value),
-        }
+        Self::with_finger(allocator.alloc(value), allocator)
+    }
+
+    /// Allocates memory in the arena and moves the `value` into it, pinning it in place.
+    ///
+    /// The value is dropped when the returned [`Box<'b, T>`](self::Box) is dropped, and its
+    /// memory is not reused before then.
+    ///
+    /// # Safety
+    ///
+    /// The returned [`Box<'b, T>`](self::Box) must be dropped before its memory is reclaimed, so
+    /// it must not be leaked, such as with [`mem::forget`](core::mem::forget) or in a reference
+    /// cycle. The box's memory is reclaimed by any of the following:
+    /// - [`Arena::reset`](crate::Arena::reset) or
+    ///   [`Arena::reset_and_shrink`](crate::Arena::reset_and_shrink).
+    /// - [`SharedArena::reset`](crate::sync::SharedArena::reset) or
+    ///   [`SharedArena::reset_and_shrink`](crate::sync::SharedArena::reset_and_shrink).
+    /// - The end of the [`Frame`](crate::Frame), [`Checkpoint`](crate::Checkpoint) or
+    ///   [`KeepFrame`](crate::KeepFrame) that the box was allocated in, including when a
+    ///   [`try_with_frame`](Bump::try_with_frame) rolls back its allocations.
+    /// - Dropping the arena.
+    ///
+    /// See the [type level documentation](Box#pinning) for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::{Arena, boxed::Box};
+    /// use core::cell::Cell;
+    /// use core::marker::PhantomPinned;
+    ///
+    /// struct Guard<'a>(&'a Cell<bool>, PhantomPinned);
+    ///
+    /// impl Drop for Guard<'_> {
+    ///     fn drop(&mut self) {
+    ///         self.0.set(true);
+    ///     }
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let dropped = Cell::new(false);
+    ///
+    /// // Safety: the box is dropped before the arena is reset
+    /// let guard = unsafe { Box::pin(Guard(&dropped, PhantomPinned), &allocator) };
+    /// std::mem::drop(guard);
+    /// assert!(dropped.get());
+    /// ```
+    pub unsafe fn pin<'a, A: Bump<'a, 'b>>(value: T, allocator: &'a A) -> Pin<Self> {
+        // Safety: ensured by caller, the value is never moved out of the box
+        unsafe { Pin::new_unchecked(Self::new(value, allocator)) }
     }
 }
 
@@ -77,9 +176,7 @@ impl<'b, T> Box<'b, MaybeUninit<T>> {
     /// assert_eq!(*six, 6);
     /// ```
     pub fn new_uninit<'a, A: Bump<'a, 'b>>(allocator: &'a A) -> Self {
-        Self {
-            value: allocator.alloc_uninit::<T>(),
-        }
+        Self::with_finger(allocator.alloc_uninit::<T>(), allocator)
     }
 }
 
@@ -89,9 +186,7 @@ impl<'b, T> Box<'b, [T]> {
     where
         T: Copy,
     {
-        Self {
-            value: allocator.alloc_slice(slice),
-        }
+        Self::with_finger(allocator.alloc_slice(slice), allocator)
     }
 
     /// Allocates memory in the arena for a slice, using the closure to fill the slice.
@@ -115,9 +210,7 @@ impl<'b, T> Box<'b, [T]> {
         A: Bump<'a, 'b>,
         F: FnMut(usize) -> T,
     {
-        Self {
-            value: allocator.alloc_slice_with(length, f),
-        }
+        Self::with_finger(allocator.alloc_slice_with(length, f), allocator)
     }
 
     /// Allocates memory in the arena for a slice to contain the values yielded by an iterator.
@@ -146,9 +239,7 @@ impl<'b, T> Box<'b, [T]> {
         I::IntoIter: ExactSizeIterator,
         A: Bump<'a, 'b>,
     {
-        Self {
-            value: allocator.alloc_slice_from_iter(items),
-        }
+        Self::with_finger(allocator.alloc_slice_from_iter(items), allocator)
     }
 
     /// Allocates memory in the arena for a slice to contain the values yielded by an iterator that
@@ -174,7 +265,87 @@ impl<'b, T> Box<'b, [T]> {
     {
         allocator
             .alloc_slice_try_from_iter(items)
-            .map(|value| Self { value })
+            .map(|value| Self::with_finger(value, allocator))
+    }
+
+    /// Extends the slice to the given length, filling the new items with clones of the `value`.
+    ///
+    /// The slice is grown in place if it is the most recent allocation made by the `allocator`,
+    /// otherwise it is moved into a new allocation. If the `new_length` is not larger, the slice
+    /// is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the new items could not be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::{Arena, boxed::Box};
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    ///
+    /// let mut names = Box::new_with(&allocator, 2, |i| i.to_string());
+    /// names.grow(4, String::from("new"), &allocator);
+    /// assert_eq!(names.as_ref(), ["0", "1", "new", "new"]);
+    ///
+    /// names.shrink(1, &allocator);
+    /// assert_eq!(names.as_ref(), ["0"]);
+    /// ```
+    pub fn grow<'a, A: Bump<'a, 'b>>(&mut self, new_length: usize, value: T, allocator: &'a A)
+    where
+        T: Clone,
+    {
+        self.try_grow(new_length, value, allocator).unwrap()
+    }
+
+    /// Attempts to extend the slice to the given length, filling the new items with clones of
+    /// the `value`.
+    ///
+    /// See [`grow`](Box::grow) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`](crate::AllocError) if any calls to an underlying memory
+    /// allocator fail, in which case the slice is left unchanged.
+    pub fn try_grow<'a, A: Bump<'a, 'b>>(
+        &mut self,
+        new_length: usize,
+        value: T,
+        allocator: &'a A,
+    ) -> Result<(), crate::AllocError>
+    where
+        T: Clone,
+    {
+        let original: *mut [T] = self.value;
+        let items = core::mem::take(&mut self.value);
+
+        // Safety: the box owns the items, and the original reference is replaced below
+        match unsafe { allocator.try_grow_slice(items, new_length, value) } {
+            Ok(items) => {
+                self.finger = Self::finger_of(items, allocator);
+                self.value = items;
+                Ok(())
+            }
+            Err(error) => {
+                // Safety: the slice is left unchanged on failure
+                self.value = unsafe { &mut *original };
+                Err(error)
+            }
+        }
+    }
+
+    /// Shortens the slice to the given length, dropping the removed items.
+    ///
+    /// If the slice is the most recent allocation made by the `allocator`, then the memory for
+    /// the removed items is reclaimed. If the `new_length` is not smaller, the slice is left
+    /// unchanged.
+    pub fn shrink<'a, A: Bump<'a, 'b>>(&mut self, new_length: usize, allocator: &'a A) {
+        let items = core::mem::take(&mut self.value);
+
+        // Safety: the box owns the items, and the original reference is replaced
+        self.value = unsafe { allocator.shrink_slice(items, new_length) };
     }
 }
 
@@ -199,8 +370,19 @@ impl<'b, T: ?Sized> Box<'b, T> {
 
 impl<T: ?Sized> core::ops::Drop for Box<'_, T> {
     fn drop(&mut self) {
+        let layout = core::alloc::Layout::for_value::<T>(self.value);
+        let pointer = NonNull::from(&mut *self.value).cast::<u8>();
+
         // Safety: self is the sole owner of the reference to T, so destructor will only be run once
         unsafe { core::ptr::drop_in_place(self.value) }
+
+        if let Some(finger) = self.finger {
+            // Safety: the finger belongs to the chunk containing the value, which is not used
+            // after this point
+            unsafe {
+                crate::raw_arena::dealloc_at_finger(finger, pointer, layout);
+            }
+        }
     }
 }
 
@@ -246,6 +428,7 @@ impl<T> core::default::Default for Box<'_, [T]> {
     fn default() -> Self {
         Self {
             value: Default::default(),
+            finger: None,
         }
     }
 }
@@ -254,10 +437,37 @@ impl core::default::Default for Box<'_, str> {
     fn default() -> Self {
         Self {
             value: Default::default(),
+            finger: None,
         }
     }
 }
 
+impl<T: PartialEq + ?Sized> PartialEq for Box<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&*self.value, &*other.value)
+    }
+}
+
+impl<T: Eq + ?Sized> Eq for Box<'_, T> {}
+
+impl<T: PartialOrd + ?Sized> PartialOrd for Box<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        PartialOrd::partial_cmp(&*self.value, &*other.value)
+    }
+}
+
+impl<T: Ord + ?Sized> Ord for Box<'_, T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        Ord::cmp(&*self.value, &*other.value)
+    }
+}
+
+impl<T: core::hash::Hash + ?Sized> core::hash::Hash for Box<'_, T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::hash::Hash::hash(&*self.value, state)
+    }
+}
+
 impl<T: core::fmt::Debug + ?Sized> core::fmt::Debug for Box<'_, T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         core::fmt::Debug::fmt(self.value, f)
@@ -322,3 +532,72 @@ impl<T: core::iter::DoubleEndedIterator + ?Sized> core::iter::DoubleEndedIterato
 }
 
 impl<T: core::iter::FusedIterator + ?Sized> core::iter::FusedIterator for Box<'_, T> {}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::Box;
+    use crate::prelude::*;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn drop_reclaims_most_recent_box() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        allocator.alloc(0u8);
+        let before = allocator.stats();
+
+        let first = Box::new(String::from("first"), &allocator);
+        let second = Box::new_with(&allocator, 3, |i| i.to_string());
+        let allocated = allocator.stats();
+        assert_eq!(second.as_ref(), &["0", "1", "2"]);
+
+        // Not the most recent allocation, so memory is kept
+        drop(first);
+        assert_eq!(allocator.stats(), allocated);
+
+        drop(second);
+        assert!(allocator.stats().allocated_bytes < allocated.allocated_bytes);
+
+        // Plain data is reclaimed too, and so are boxes dropped on other threads
+        let number = Box::new(5u64, &allocator);
+        let with_number = allocator.stats();
+        assert!(with_number.allocated_bytes > before.allocated_bytes);
+        drop(number);
+        assert!(allocator.stats().allocated_bytes < with_number.allocated_bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn boxes_can_be_dropped_on_other_threads() {
+        fn is_send_sync<T: Send + Sync>(_: &T) {}
+
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let before = allocator.stats();
+        let numbers = Box::new_slice(&[1u32, 2, 3], &allocator);
+        is_send_sync(&numbers);
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || assert_eq!(numbers.iter().sum::<u32>(), 6));
+        });
+        assert_eq!(allocator.stats().allocated_bytes, before.allocated_bytes);
+    }
+
+    #[test]
+    fn grow_and_shrink_drop_items_once() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let counter = alloc::rc::Rc::new(());
+
+        let mut items = Box::new_with(&allocator, 2, |_| counter.clone());
+        allocator.alloc(0u8);
+        items.grow(5, counter.clone(), &allocator);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 6);
+
+        items.shrink(1, &allocator);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 2);
+
+        drop(items);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,243 @@
+//! Contains the [`ArenaBuilder`] used to configure arenas.
+
+use crate::limit::MemoryLimit;
+use crate::raw_arena::{ChunkPolicy, RawArena};
+use crate::{Arena, ChunkAllocator, Global};
+use core::num::NonZeroUsize;
+
+/// Determines how the capacity of each new chunk allocated by an arena grows.
+///
+/// The capacity of a new chunk is always large enough to contain the allocation request that
+/// caused it to be allocated.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ChunkGrowth {
+    /// Each new chunk has twice the capacity of the previous chunk.
+    #[default]
+    Doubling,
+    /// Each new chunk's capacity is the previous chunk's capacity multiplied by the given factor.
+    Factor(NonZeroUsize),
+    /// Each new chunk's capacity is the previous chunk's capacity plus the given number of bytes.
+    Linear(usize),
+    /// Each new chunk has the initial capacity.
+    Fixed,
+}
+
+/// Configures the size of chunks allocated by an [`Arena`] or
+/// [`SharedArena`](crate::sync::SharedArena).
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{ArenaBuilder, Bump, ChunkGrowth};
+///
+/// let mut arena = ArenaBuilder::new()
+///     .initial_chunk_size(256)
+///     .chunk_growth(ChunkGrowth::Linear(256))
+///     .max_chunk_size(4096)
+///     .build();
+///
+/// let allocator = arena.allocator();
+/// assert_eq!(allocator.alloc_slice_fill(300, 1u8).len(), 300);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ArenaBuilder<A: ChunkAllocator = Global> {
+    allocator: A,
+    policy: ChunkPolicy,
+    memory_limit: Option<usize>,
+}
+
+impl ArenaBuilder {
+    /// Creates a builder for arenas that allocate chunks using the [`Global`] allocator.
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<A: ChunkAllocator> ArenaBuilder<A> {
+    /// Creates a builder for arenas that allocate chunks with the given [`ChunkAllocator`].
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            allocator,
+            policy: ChunkPolicy::DEFAULT,
+            memory_limit: None,
+        }
+    }
+
+    /// Sets the capacity, in bytes, of the first chunk allocated by the arena.
+    ///
+    /// Defaults to `1024` bytes.
+    pub fn initial_chunk_size(mut self, size: usize) -> Self {
+        self.policy.initial = size;
+        self
+    }
+
+    /// Sets how the capacity of each subsequent chunk grows.
+    ///
+    /// Defaults to [`ChunkGrowth::Doubling`].
+    pub fn chunk_growth(mut self, growth: ChunkGrowth) -> Self {
+        self.policy.growth = growth;
+        self
+    }
+
+    /// Sets the minimum capacity, in bytes, of any chunk allocated by the arena.
+    pub fn min_chunk_size(mut self, size: usize) -> Self {
+        self.policy.minimum = size;
+        self
+    }
+
+    /// Sets the maximum capacity, in bytes, that a chunk can grow to.
+    ///
+    /// Chunks may still exceed this capacity if they are allocated to contain a single large
+    /// allocation request. If the minimum chunk size is larger, then the minimum takes precedence.
+    pub fn max_chunk_size(mut self, size: usize) -> Self {
+        self.policy.maximum = size;
+        self
+    }
+
+    /// Sets the maximum total number of bytes, including chunk headers, that the arena's chunks
+    /// can occupy.
+    ///
+    /// Allocations that would require a new chunk past this limit fail with
+    /// [`AllocError::LimitExceeded`](crate::AllocError::LimitExceeded), causing a panic for
+    /// infallible allocation methods such as [`Bump::alloc`](crate::Bump::alloc). For a
+    /// [`SharedArena`](crate::sync::SharedArena), the limit applies to all of its arenas combined.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::{AllocError, ArenaBuilder, Bump};
+    ///
+    /// let mut arena = ArenaBuilder::new().memory_limit(4096).build();
+    /// let allocator = arena.allocator();
+    /// assert!(allocator.try_alloc_slice_fill(1024, 0u8).is_ok());
+    /// assert_eq!(
+    ///     allocator.try_alloc_slice_fill(8192, 0u8).err(),
+    ///     Some(AllocError::LimitExceeded)
+    /// );
+    /// ```
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Creates an empty [`Arena`] with the configured settings.
+    pub fn build(self) -> Arena<A> {
+        let limit = match self.memory_limit {
+            Some(limit) => MemoryLimit::Local(limit),
+            None => MemoryLimit::Unlimited,
+        };
+
+        Arena::with_raw_arena(RawArena::with_settings_in(
+            self.policy,
+            limit,
+            self.allocator,
+        ))
+    }
+
+    /// Creates an empty [`SharedArena`](crate::sync::SharedArena) with the configured settings,
+    /// which apply to every [`ThreadAllocator`](crate::sync::ThreadAllocator) that it hands out.
+    #[cfg(feature = "sync")]
+    pub fn build_shared(self) -> crate::sync::SharedArena<A>
+    where
+        A: Clone,
+    {
+        crate::sync::SharedArena::with_settings_in(self.policy, self.memory_limit, self.allocator)
+    }
+}
+
+impl Default for ArenaBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+    use crate::{ArenaBuilder, ChunkGrowth};
+    use alloc::vec::Vec;
+    use core::num::NonZeroUsize;
+
+    /// Allocates a slice of each of the given sizes, then returns the capacity of every chunk.
+    fn chunk_capacities(arena: &mut Arena, sizes: &[usize]) -> Vec<usize> {
+        let allocator = arena.allocator();
+        for &size in sizes {
+            allocator.alloc_slice_fill(size, 0u8);
+        }
+
+        arena.chunks().map(|chunk| chunk.capacity()).collect()
+    }
+
+    /// Chunks are rounded up to a multiple of 16 bytes, so their capacity can be slightly larger.
+    fn assert_capacity(capacity: usize, expected: usize) {
+        assert!(
+            (expected..expected + 16).contains(&capacity),
+            "expected a capacity of {expected} bytes, got {capacity}"
+        );
+    }
+
+    #[test]
+    fn fixed_chunk_growth_respects_limits() {
+        let mut arena = ArenaBuilder::new()
+            .initial_chunk_size(256)
+            .chunk_growth(ChunkGrowth::Fixed)
+            .build();
+
+        let capacities = chunk_capacities(&mut arena, &[256; 4]);
+        assert_eq!(capacities.len(), 4);
+        assert_capacity(capacities[0], 256);
+        assert!(capacities.iter().all(|&capacity| capacity == capacities[0]));
+
+        // Requests larger than the chunk size still succeed
+        let capacities = chunk_capacities(&mut arena, &[1000]);
+        assert_eq!(capacities.len(), 5);
+        assert_capacity(capacities[4], 1000);
+    }
+
+    #[test]
+    fn linear_chunk_growth_adds_increment() {
+        let mut arena = ArenaBuilder::new()
+            .initial_chunk_size(256)
+            .chunk_growth(ChunkGrowth::Linear(128))
+            .build();
+
+        let capacities = chunk_capacities(&mut arena, &[256, 384, 512, 640]);
+        assert_eq!(capacities.len(), 4);
+        assert_capacity(capacities[0], 256);
+        for pair in capacities.windows(2) {
+            assert_eq!(pair[1] - pair[0], 128);
+        }
+    }
+
+    #[test]
+    fn factor_chunk_growth_multiplies_capacity() {
+        let mut arena = ArenaBuilder::new()
+            .initial_chunk_size(64)
+            .chunk_growth(ChunkGrowth::Factor(NonZeroUsize::new(3).unwrap()))
+            .build();
+
+        let capacities = chunk_capacities(&mut arena, &[64, 192, 576, 1728]);
+        assert_eq!(capacities.len(), 4);
+        assert_capacity(capacities[0], 64);
+        for pair in capacities.windows(2) {
+            assert_capacity(pair[1], pair[0] * 3);
+        }
+    }
+
+    #[test]
+    fn chunk_capacities_are_clamped() {
+        let mut arena = ArenaBuilder::new()
+            .initial_chunk_size(64)
+            .min_chunk_size(128)
+            .max_chunk_size(512)
+            .build();
+
+        let capacities = chunk_capacities(&mut arena, &[128, 256, 512, 512]);
+        assert_eq!(capacities.len(), 4);
+        assert_capacity(capacities[0], 128);
+        assert_capacity(capacities[1], capacities[0] * 2);
+        assert_capacity(capacities[2], 512);
+        assert_capacity(capacities[3], 512);
+    }
+}
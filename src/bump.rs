@@ -1,18 +1,227 @@
 //! Contains the [`Bump`] trait.
 
 use crate::private::Try;
+use crate::{AllocError, ArenaRef};
 use core::alloc::Layout;
+use core::convert::Infallible;
 use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 
-mod private {
-    pub trait Sealed {}
+pub(crate) mod private {
+    use core::sync::atomic::AtomicPtr;
 
-    impl Sealed for crate::Allocator<'_> {}
-    impl Sealed for crate::Frame<'_, '_> {}
+    pub trait Sealed {
+        /// Returns the finger of the chunk that objects are currently allocated in, if objects
+        /// allocated by this allocator can be safely deallocated through it for as long as they
+        /// live.
+        fn current_finger(&self) -> Option<&AtomicPtr<u8>>;
+    }
+}
+
+/// Implements the required methods of [`Bump`] by forwarding them to a `self.arena` field that
+/// dereferences to a [`RawArena`](crate::raw_arena::RawArena), where `$a` is the lifetime of the
+/// allocations.
+macro_rules! forward_bump_methods {
+    ($me:lifetime, $a:lifetime) => {
+        #[inline(always)]
+        fn alloc_with_layout(&$me self, layout: core::alloc::Layout) -> core::ptr::NonNull<u8> {
+            self.arena.alloc_with_layout(layout)
+        }
+
+        #[inline(always)]
+        fn try_alloc_with_layout(
+            &$me self,
+            layout: core::alloc::Layout,
+        ) -> Result<core::ptr::NonNull<u8>, crate::AllocError> {
+            self.arena.try_alloc_with_layout(layout)
+        }
+
+        #[inline(always)]
+        fn with_frame<T, F>(&$me mut self, f: F) -> T
+        where
+            F: FnOnce(&mut crate::Frame<'_, '_, Self::ChunkAllocator>) -> T,
+        {
+            crate::Frame::in_arena(&mut *self.arena, f)
+        }
+
+        #[inline(always)]
+        fn try_with_frame_keep<T, F>(&$me mut self, f: F) -> Result<T, crate::AllocError>
+        where
+            F: FnOnce(&mut crate::KeepFrame<$a, '_, Self::ChunkAllocator>) -> T,
+        {
+            crate::KeepFrame::try_in_arena(&mut *self.arena, f)
+        }
+
+        #[inline(always)]
+        unsafe fn alloc_try_with_layout<R, F>(&$me self, layout: core::alloc::Layout, f: F) -> R
+        where
+            R: crate::private::Try,
+            F: FnOnce(core::ptr::NonNull<u8>) -> R,
+        {
+            // Safety: ensured by caller
+            unsafe { self.arena.alloc_try_with_layout(layout, f) }
+        }
+
+        #[inline(always)]
+        fn try_with_frame<T, E, F>(&$me mut self, f: F) -> Result<(T, &$me mut Self), E>
+        where
+            F: FnOnce(&mut crate::Frame<'_, $me, Self::ChunkAllocator>) -> Result<T, E>,
+        {
+            let arena: *mut crate::raw_arena::RawArena<Self::ChunkAllocator> = &mut *self.arena;
+            // Safety: the frame only uses the arena until the closure returns, since it cannot be
+            // swapped with another frame. Only its allocations live for $me.
+            let value = crate::Frame::try_in_arena(unsafe { &mut *arena }, f)?;
+            Ok((value, self))
+        }
+
+        #[inline(always)]
+        unsafe fn dealloc_last(
+            &$me self,
+            pointer: core::ptr::NonNull<u8>,
+            layout: core::alloc::Layout,
+        ) -> bool {
+            // Safety: ensured by caller
+            unsafe { self.arena.dealloc_last(pointer, layout) }
+        }
+
+        #[inline(always)]
+        unsafe fn try_alloc_with_drop_unchecked<T>(
+            &$me self,
+            value: T,
+        ) -> Result<&$a mut T, crate::AllocError> {
+            // Safety: ensured by caller, value lives until the arena is reset
+            unsafe { Ok(self.arena.try_alloc_with_drop(value)?.as_mut()) }
+        }
+
+        #[inline(always)]
+        unsafe fn realloc_last(
+            &$me self,
+            pointer: core::ptr::NonNull<u8>,
+            old_layout: core::alloc::Layout,
+            new_layout: core::alloc::Layout,
+        ) -> Option<core::ptr::NonNull<u8>> {
+            // Safety: ensured by caller
+            unsafe { self.arena.realloc_last(pointer, old_layout, new_layout) }
+        }
+
+        #[inline(always)]
+        unsafe fn try_realloc_with_layout(
+            &$me self,
+            pointer: core::ptr::NonNull<u8>,
+            old_layout: core::alloc::Layout,
+            new_layout: core::alloc::Layout,
+        ) -> Result<core::ptr::NonNull<u8>, crate::AllocError> {
+            // Safety: ensured by caller
+            unsafe { self.arena.realloc(pointer, old_layout, new_layout) }
+        }
+    };
+}
+
+pub(crate) use forward_bump_methods;
+
+#[inline(always)]
+fn slice_layout<T>(length: usize) -> Result<Layout, AllocError> {
+    Layout::array::<T>(length).map_err(|_| AllocError::LayoutTooLarge)
+}
+
+/// Moves the items yielded by an iterator into a slice with room for `capacity` items, moving it
+/// into a larger allocation whenever it runs out of room. Once the iterator is exhausted, any
+/// unused space at the end of the slice is reclaimed.
+///
+/// If the iterator yields an error, or if memory could not be allocated, then the items that
+/// were already moved are dropped, and the slice is deallocated if it is still the most recent
+/// allocation.
+///
+/// # Safety
+///
+/// The `destination` must have been allocated by the `bump` allocator with room for `capacity`
+/// items, and must no longer be used after this call.
+unsafe fn try_fill_slice<'me, 'a, B, T, E, I>(
+    bump: &'me B,
+    mut destination: NonNull<T>,
+    mut capacity: usize,
+    items: I,
+) -> Result<Result<&'a mut [T], E>, AllocError>
+where
+    B: Bump<'me, 'a> + ?Sized,
+    I: Iterator<Item = Result<T, E>>,
+{
+    let mut length = 0usize;
+    let discard = |destination: NonNull<T>, capacity: usize, length: usize| {
+        // Safety: first items are initialized, and are no longer used
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                destination.as_ptr(),
+                length,
+            ));
+        }
+
+        // Layout is valid, since the destination was already allocated
+        let layout = slice_layout::<T>(capacity).unwrap();
 
-    #[cfg(feature = "sync")]
-    impl Sealed for crate::sync::ThreadAllocator<'_> {}
+        // Safety: destination was allocated with the layout, and is no longer used
+        unsafe { bump.dealloc_last(destination.cast(), layout) };
+    };
+
+    for item in items {
+        let item = match item {
+            Ok(item) => item,
+            Err(error) => {
+                discard(destination, capacity, length);
+                return Ok(Err(error));
+            }
+        };
+
+        if length == capacity {
+            let grown = capacity
+                .checked_mul(2)
+                .ok_or(AllocError::LayoutTooLarge)
+                .and_then(|new_capacity| {
+                    let new_capacity = new_capacity.max(4);
+                    let old_layout = slice_layout::<T>(capacity)?;
+                    let new_layout = slice_layout::<T>(new_capacity)?;
+
+                    // Safety: destination is valid for the old layout, and is no longer used once
+                    // it is moved
+                    let moved = unsafe {
+                        bump.try_realloc_with_layout(destination.cast(), old_layout, new_layout)?
+                    };
+
+                    Ok((moved.cast::<T>(), new_capacity))
+                });
+
+            match grown {
+                Ok((moved, new_capacity)) => {
+                    destination = moved;
+                    capacity = new_capacity;
+                }
+                Err(error) => {
+                    discard(destination, capacity, length);
+                    return Err(error);
+                }
+            }
+        }
+
+        // Safety: length is less than the capacity
+        unsafe { destination.as_ptr().add(length).write(item) }
+        length += 1;
+    }
+
+    // Layouts are valid, since the destination was already allocated
+    let old_layout = slice_layout::<T>(capacity).unwrap();
+    let new_layout = slice_layout::<T>(length).unwrap();
+    let pointer = destination.cast::<u8>();
+
+    // Safety: destination is valid for the old layout, and is no longer used once it is moved
+    let destination = unsafe { bump.realloc_last(pointer, old_layout, new_layout) }
+        .unwrap_or(pointer)
+        .cast::<T>()
+        .as_ptr();
+
+    // Safety: first items are initialized, allocation lives for 'a
+    Ok(Ok(unsafe {
+        core::slice::from_raw_parts_mut(destination, length)
+    }))
 }
 
 /// Contains methods for bump allocation.
@@ -28,9 +237,77 @@ mod private {
 /// Additionally, requests to allocate zero-sized values must yield a pointer that can be
 /// transmuted into a valid mutable reference.
 pub unsafe trait Bump<'me, 'a>: private::Sealed {
+    /// The [`ChunkAllocator`](crate::ChunkAllocator) used to allocate the chunks of the
+    /// underlying arena.
+    type ChunkAllocator: crate::ChunkAllocator;
+
     /// Calls a closure with a [`Frame`](crate::Frame) used to tie the lifetime of allocations made
     /// into an arena to a stack frame.
-    fn with_frame<T, F: FnOnce(&mut crate::Frame) -> T>(&'me mut self, f: F) -> T;
+    fn with_frame<T, F>(&'me mut self, f: F) -> T
+    where
+        F: FnOnce(&mut crate::Frame<'_, '_, Self::ChunkAllocator>) -> T;
+
+    /// Calls a closure with a [`KeepFrame`](crate::KeepFrame), which allows cloning values out of
+    /// the frame into this allocator before the rest of the frame's allocations are freed.
+    ///
+    /// The frame's allocations are made in a separate chunk, so a new chunk may be allocated
+    /// even if the frame only allocates a small amount of memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    ///
+    /// let mut arena = Arena::new();
+    /// let mut allocator = arena.allocator();
+    /// let longest = allocator.with_frame_keep(|frame| {
+    ///     // Lots of scratch memory
+    ///     let words = (0..1000).map(|i| &*frame.alloc_str(&i.to_string()));
+    ///     let words = frame.alloc_slice_from_iter(words);
+    ///     let longest = words.iter().max_by_key(|word| word.len()).unwrap();
+    ///     frame.keep(longest)
+    /// });
+    ///
+    /// assert_eq!(longest, "999");
+    /// assert!(allocator.stats().allocated_bytes < 100);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a chunk for the frame's allocations could not be allocated, which can happen
+    /// even if the frame does not allocate anything, see
+    /// [`try_with_frame_keep`](Bump::try_with_frame_keep).
+    #[inline(always)]
+    fn with_frame_keep<T, F>(&'me mut self, f: F) -> T
+    where
+        F: FnOnce(&mut crate::KeepFrame<'a, '_, Self::ChunkAllocator>) -> T,
+    {
+        self.try_with_frame_keep(f).unwrap()
+    }
+
+    /// Attempts to call a closure with a [`KeepFrame`](crate::KeepFrame).
+    ///
+    /// See [`with_frame_keep`](Bump::with_frame_keep) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if a chunk for the frame's allocations could not be allocated,
+    /// such as when it would exceed the arena's [memory limit](crate::ArenaBuilder::memory_limit).
+    /// The closure is not called in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::{prelude::*, ArenaBuilder};
+    ///
+    /// let mut arena = ArenaBuilder::new().memory_limit(1024).build();
+    /// let mut allocator = arena.allocator();
+    /// allocator.alloc(5u32);
+    /// assert!(allocator.try_with_frame_keep(|_| ()).is_err());
+    /// ```
+    fn try_with_frame_keep<T, F>(&'me mut self, f: F) -> Result<T, AllocError>
+    where
+        F: FnOnce(&mut crate::KeepFrame<'a, '_, Self::ChunkAllocator>) -> T;
 
     /// Allocates space for an object with the given [`Layout`], returning a valid pointer to it.
     ///
@@ -39,6 +316,14 @@ pub unsafe trait Bump<'me, 'a>: private::Sealed {
     /// Panics if any calls to an underlying memory allocator fail.
     fn alloc_with_layout(&'me self, layout: Layout) -> NonNull<u8>;
 
+    /// Attempts to allocate space for an object with the given [`Layout`], returning a valid
+    /// pointer to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
+    fn try_alloc_with_layout(&'me self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
     /// Allocates space for an object with the given [`Layout`], and passes the pointer to a
     /// closure that returns a [`Result<T>`] or [`Option<T>`].
     ///
@@ -53,6 +338,184 @@ pub unsafe trait Bump<'me, 'a>: private::Sealed {
         R: Try,
         F: FnOnce(NonNull<u8>) -> R;
 
+    /// Calls a closure that allocates a group of objects, keeping them only if it returns [`Ok`].
+    ///
+    /// If the closure returns [`Ok`], then the objects it allocated with the
+    /// [`Frame`](crate::Frame) are kept in this allocator, and its result is returned along with
+    /// this allocator, which can keep allocating while the result is in use. If it returns
+    /// [`Err`], or if it panics, then every object allocated within the closure is deallocated,
+    /// all at once.
+    ///
+    /// Objects allocated by the closure live for as long as this allocator is mutably borrowed,
+    /// so they can be returned in either case. The allocator is not returned on failure, so the
+    /// memory of a failed frame is only reused once references to it, such as in the error, are
+    /// gone.
+    ///
+    /// Objects allocated with [`alloc_with_drop`](Bump::alloc_with_drop) are never dropped early.
+    /// If the closure allocates any, then nothing that it allocated is deallocated until the
+    /// enclosing frame ends or the arena is reset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    ///
+    /// type Parsed<'a, 'b> = (&'a [&'a str], &'a mut Allocator<'b>);
+    ///
+    /// fn parse<'a, 'b>(allocator: &'a mut Allocator<'b>, text: &str) -> Result<Parsed<'a, 'b>, String> {
+    ///     allocator.try_with_frame(|frame| {
+    ///         let mut words = Vec::new();
+    ///         for word in text.split(',') {
+    ///             if word.is_empty() {
+    ///                 return Err(format!("missing word in {text:?}"));
+    ///             }
+    ///             words.push(&*frame.alloc_str(word));
+    ///         }
+    ///         Ok(&*frame.alloc_slice_cloned(&words))
+    ///     })
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let mut allocator = arena.allocator();
+    /// let (words, allocator) = parse(&mut allocator, "a,b,c").unwrap();
+    /// let joined = allocator.alloc_str(&words.join("+"));
+    /// assert_eq!(words, ["a", "b", "c"]);
+    /// assert_eq!(joined, "a+b+c");
+    ///
+    /// let before = allocator.stats();
+    /// assert!(parse(allocator, "d,e,,f").is_err());
+    /// assert_eq!(allocator.stats(), before);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use bumpercar::prelude::*;
+    ///
+    /// let mut arena = Arena::new();
+    /// let mut allocator = arena.allocator();
+    /// let failed = allocator
+    ///     .try_with_frame(|frame| Err::<(), _>(&*frame.alloc_str("no")))
+    ///     .err();
+    /// // Does not compile, the memory of the failed frame cannot be reused while it is borrowed
+    /// allocator.alloc_str("yes");
+    /// println!("{failed:?}");
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use bumpercar::prelude::*;
+    ///
+    /// let mut first_arena = Arena::new();
+    /// let mut second_arena = Arena::new();
+    /// let mut first = first_arena.allocator();
+    /// let mut second = second_arena.allocator();
+    /// let _ = first.try_with_frame(|outer| {
+    ///     // Does not compile, the frames would roll back each other's arenas
+    ///     let _ = second.try_with_frame(|inner| Err::<(), ()>(core::mem::swap(outer, inner)));
+    ///     Ok::<(), ()>(())
+    /// });
+    /// ```
+    fn try_with_frame<T, E, F>(&'me mut self, f: F) -> Result<(T, &'me mut Self), E>
+    where
+        F: FnOnce(&mut crate::Frame<'_, 'me, Self::ChunkAllocator>) -> Result<T, E>;
+
+    /// Deallocates the object at `pointer` if it is the most recent allocation, returning `true`
+    /// if its memory was reclaimed.
+    ///
+    /// Otherwise, this does nothing, and the object's memory is only reclaimed once the arena is
+    /// reset.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must refer to an object allocated by this allocator with the given `layout`,
+    /// and the object must no longer be used after this call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    /// use core::alloc::Layout;
+    /// use core::ptr::NonNull;
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let first = NonNull::from(allocator.alloc(1u64)).cast::<u8>();
+    /// let before = allocator.stats();
+    /// let second = NonNull::from(allocator.alloc(2u64)).cast::<u8>();
+    ///
+    /// unsafe {
+    ///     // Not the most recent allocation, so nothing happens
+    ///     assert!(!allocator.dealloc_last(first, Layout::new::<u64>()));
+    ///     assert!(allocator.dealloc_last(second, Layout::new::<u64>()));
+    /// }
+    ///
+    /// assert_eq!(allocator.stats(), before);
+    /// ```
+    unsafe fn dealloc_last(&'me self, pointer: NonNull<u8>, layout: Layout) -> bool;
+
+    /// Attempts to resize the object at `pointer` within the current chunk, returning a pointer to
+    /// the resized object.
+    ///
+    /// Since the arena bumps downwards, the end of the object stays in place, so its contents are
+    /// moved towards the start of the chunk when it grows, and towards the end when it shrinks.
+    ///
+    /// Returns [`None`] if the object is not the most recent allocation, or if the current chunk
+    /// does not have enough space remaining.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must be valid for reads and writes of `old_layout.size()` bytes, and must be
+    /// aligned to `old_layout.align()`. If [`Some`] is returned, then the object's contents are
+    /// moved, and the `pointer` must no longer be used.
+    unsafe fn realloc_last(
+        &'me self,
+        pointer: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Option<NonNull<u8>>;
+
+    /// Attempts to resize the object at `pointer`, returning a pointer to the resized object.
+    ///
+    /// The object is resized in place if possible, see [`realloc_last`](Bump::realloc_last).
+    /// Otherwise, it is copied into a new allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the object is left unchanged.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must be valid for reads and writes of `old_layout.size()` bytes, and must be
+    /// aligned to `old_layout.align()`. If [`Ok`] is returned, then the object's contents are
+    /// moved, and the `pointer` must no longer be used.
+    unsafe fn try_realloc_with_layout(
+        &'me self,
+        pointer: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, AllocError>;
+
+    /// Attempts to move the `value` into the arena, registering it so that its destructor is run
+    /// when the arena is reset, when the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// See [`alloc_with_drop`](Bump::alloc_with_drop) for a safe version of this method.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    ///
+    /// # Safety
+    ///
+    /// Since arenas can be sent to other threads, and destructors run after the lifetime `'a`
+    /// ends, callers must ensure that:
+    /// - If `T` is not [`Send`], then the arena is reset or dropped on the thread that allocated
+    ///   the `value`.
+    /// - Any data borrowed by the `value` is still valid when the arena is reset or dropped.
+    unsafe fn try_alloc_with_drop_unchecked<T>(
+        &'me self,
+        value: T,
+    ) -> Result<&'a mut T, AllocError>;
+
     /// Allocates space for an instance of `T`.
     #[inline(always)]
     fn alloc_uninit<T>(&'me self) -> &'a mut MaybeUninit<T> {
@@ -60,6 +523,19 @@ pub unsafe trait Bump<'me, 'a>: private::Sealed {
         unsafe { self.alloc_with_layout(Layout::new::<T>()).cast().as_mut() }
     }
 
+    /// Attempts to allocate space for an instance of `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
+    #[inline(always)]
+    fn try_alloc_uninit<T>(&'me self) -> Result<&'a mut MaybeUninit<T>, AllocError> {
+        let allocation = self.try_alloc_with_layout(Layout::new::<T>())?;
+
+        // Safety: passed layout ensures proper alignment
+        Ok(unsafe { allocation.cast().as_mut() })
+    }
+
     /// Allocates space for an instance of `T`, and provides a pointer to `T` to a closure.
     ///
     /// If the closure returns [`Err`] or [`None`], then the object is deallocated.
@@ -79,69 +555,560 @@ pub unsafe trait Bump<'me, 'a>: private::Sealed {
             })
         };
 
-        // Safety: parameter of F is tied to a lifetime, errors won't cause dangling pointers
-        unsafe { self.alloc_try_with_layout::<R, _>(Layout::new::<T>(), alloc_f) }
+        // Safety: parameter of F is tied to a lifetime, errors won't cause dangling pointers
+        unsafe { self.alloc_try_with_layout::<R, _>(Layout::new::<T>(), alloc_f) }
+    }
+
+    /// Allocates space for an instance of `T`, and initializes it with the given closure.
+    #[inline(always)]
+    fn alloc_with<T, F: FnOnce() -> T>(&'me self, f: F) -> &'a mut T {
+        self.alloc_uninit::<T>().write(f())
+    }
+
+    /// Attempts to allocate space for an instance of `T`, and initializes it with the given
+    /// closure.
+    ///
+    /// The closure is only called if the allocation succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
+    #[inline(always)]
+    fn try_alloc_with<T, F: FnOnce() -> T>(&'me self, f: F) -> Result<&'a mut T, AllocError> {
+        Ok(self.try_alloc_uninit::<T>()?.write(f()))
+    }
+
+    /// Allocates space for an instance of `T`, and moves the value into the allocation.
+    #[inline(always)]
+    fn alloc<T>(&'me self, value: T) -> &'a mut T {
+        self.alloc_with(|| value)
+    }
+
+    /// Attempts to allocate space for an instance of `T`, and moves the value into the allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    #[inline(always)]
+    fn try_alloc<T>(&'me self, value: T) -> Result<&'a mut T, AllocError> {
+        self.try_alloc_with(|| value)
+    }
+
+    /// Moves the `value` into the arena, running its destructor when the arena is reset, when
+    /// the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// Unlike [`alloc`](Bump::alloc), which never drops the `value`, this allows types that own
+    /// other resources to be stored in the arena without leaking them. Destructors are run in the
+    /// reverse of the order that the values were allocated in.
+    ///
+    /// Since the arena may be reset or dropped on another thread, `T` must be [`Send`]. See
+    /// [`alloc_with_drop_unchecked`](Bump::alloc_with_drop_unchecked) for types such as
+    /// [`Rc`](alloc::rc::Rc).
+    ///
+    /// The arena remains responsible for dropping the `value`, so the returned reference must not
+    /// be used to drop any of its contents early. This is why methods such as
+    /// [`shrink_slice`](Bump::shrink_slice) are `unsafe`:
+    ///
+    /// ```compile_fail
+    /// use bumpercar::prelude::*;
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let names = allocator.alloc_with_drop([String::from("a"), String::from("b")]);
+    /// // Would drop "b" here, and again when the arena is dropped
+    /// allocator.shrink_slice(names, 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    /// use std::sync::Arc;
+    ///
+    /// let shared = Arc::new(5);
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let owned = allocator.alloc_with_drop(Vec::from([1, 2, 3]));
+    /// owned.push(4);
+    /// allocator.alloc_with_drop(shared.clone());
+    /// assert_eq!(Arc::strong_count(&shared), 2);
+    ///
+    /// arena.reset();
+    /// assert_eq!(Arc::strong_count(&shared), 1);
+    /// ```
+    #[inline(always)]
+    fn alloc_with_drop<T: Send + 'static>(&'me self, value: T) -> &'a mut T {
+        self.try_alloc_with_drop(value).unwrap()
+    }
+
+    /// Attempts to move the `value` into the arena, running its destructor when the arena is
+    /// reset, when the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// See [`alloc_with_drop`](Bump::alloc_with_drop) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    #[inline(always)]
+    fn try_alloc_with_drop<T: Send + 'static>(
+        &'me self,
+        value: T,
+    ) -> Result<&'a mut T, AllocError> {
+        // Safety: T can be dropped on any thread, and does not borrow anything
+        unsafe { self.try_alloc_with_drop_unchecked(value) }
+    }
+
+    /// Moves the `value` into the arena, running its destructor when the arena is reset, when
+    /// the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// This allows types that are not [`Send`], such as [`Rc`](alloc::rc::Rc), or that borrow
+    /// data, to be stored in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated.
+    ///
+    /// # Safety
+    ///
+    /// See [`try_alloc_with_drop_unchecked`](Bump::try_alloc_with_drop_unchecked).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    /// use std::rc::Rc;
+    ///
+    /// let shared = Rc::new(5);
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// // Safety: arena is reset on this thread, Rc does not borrow anything
+    /// unsafe { allocator.alloc_with_drop_unchecked(shared.clone()) };
+    /// assert_eq!(Rc::strong_count(&shared), 2);
+    ///
+    /// arena.reset();
+    /// assert_eq!(Rc::strong_count(&shared), 1);
+    /// ```
+    #[inline(always)]
+    unsafe fn alloc_with_drop_unchecked<T>(&'me self, value: T) -> &'a mut T {
+        // Safety: ensured by caller
+        unsafe { self.try_alloc_with_drop_unchecked(value) }.unwrap()
+    }
+
+    /// Moves the `value` into the arena and pins it in place, running its destructor when the
+    /// arena is reset, when the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// Objects in an arena are never moved, but [`alloc`](Bump::alloc) alone is not enough to
+    /// pin them. The drop guarantee of [`Pin`](core::pin::Pin) requires a pinned value's memory
+    /// to remain valid until the value is dropped. Leaking the value forever satisfies this, but
+    /// resetting the arena reuses its memory without dropping it. This method allocates the
+    /// `value` with [`alloc_with_drop`](Bump::alloc_with_drop), so it is always dropped before
+    /// its memory is reused.
+    ///
+    /// Like [`alloc_with_drop`](Bump::alloc_with_drop), this is limited to values that are
+    /// [`Send`] and `'static`. Other values can be pinned with
+    /// [`alloc_pinned_unchecked`](Bump::alloc_pinned_unchecked).
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    /// use core::marker::PhantomPinned;
+    ///
+    /// struct Node {
+    ///     value: u32,
+    ///     _pinned: PhantomPinned,
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let node = allocator.alloc_pinned(Node { value: 5, _pinned: PhantomPinned });
+    /// assert_eq!(node.value, 5);
+    /// ```
+    #[inline(always)]
+    fn alloc_pinned<T: Send + 'static>(&'me self, value: T) -> core::pin::Pin<&'a mut T> {
+        self.try_alloc_pinned(value).unwrap()
+    }
+
+    /// Attempts to move the `value` into the arena and pin it in place, running its destructor
+    /// when the arena is reset, when the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// See [`alloc_pinned`](Bump::alloc_pinned) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    #[inline(always)]
+    fn try_alloc_pinned<T: Send + 'static>(
+        &'me self,
+        value: T,
+    ) -> Result<core::pin::Pin<&'a mut T>, AllocError> {
+        let value = self.try_alloc_with_drop(value)?;
+
+        // Safety: value is never moved, and is dropped before its memory is reused
+        Ok(unsafe { core::pin::Pin::new_unchecked(value) })
+    }
+
+    /// Moves the `value` into the arena and pins it in place, running its destructor when the
+    /// arena is reset, when the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// Unlike [`alloc_pinned`](Bump::alloc_pinned), this allows values that are not [`Send`], or
+    /// that borrow data, to be pinned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated.
+    ///
+    /// # Safety
+    ///
+    /// See [`try_alloc_with_drop_unchecked`](Bump::try_alloc_with_drop_unchecked).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    /// use core::marker::PhantomPinned;
+    /// use std::rc::Rc;
+    ///
+    /// struct Node {
+    ///     shared: Rc<u32>,
+    ///     _pinned: PhantomPinned,
+    /// }
+    ///
+    /// let shared = Rc::new(5);
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// // Safety: arena is reset on this thread, Node does not borrow anything
+    /// let node = unsafe {
+    ///     allocator.alloc_pinned_unchecked(Node {
+    ///         shared: shared.clone(),
+    ///         _pinned: PhantomPinned,
+    ///     })
+    /// };
+    /// assert_eq!(*node.shared, 5);
+    ///
+    /// arena.reset();
+    /// assert_eq!(Rc::strong_count(&shared), 1);
+    /// ```
+    #[inline(always)]
+    unsafe fn alloc_pinned_unchecked<T>(&'me self, value: T) -> core::pin::Pin<&'a mut T> {
+        // Safety: ensured by caller
+        unsafe { self.try_alloc_pinned_unchecked(value) }.unwrap()
+    }
+
+    /// Attempts to move the `value` into the arena and pin it in place, running its destructor
+    /// when the arena is reset, when the enclosing frame exits, or when the arena is dropped.
+    ///
+    /// See [`alloc_pinned_unchecked`](Bump::alloc_pinned_unchecked) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    ///
+    /// # Safety
+    ///
+    /// See [`try_alloc_with_drop_unchecked`](Bump::try_alloc_with_drop_unchecked).
+    #[inline(always)]
+    unsafe fn try_alloc_pinned_unchecked<T>(
+        &'me self,
+        value: T,
+    ) -> Result<core::pin::Pin<&'a mut T>, AllocError> {
+        // Safety: ensured by caller
+        let value = unsafe { self.try_alloc_with_drop_unchecked(value) }?;
+
+        // Safety: value is never moved, and is dropped before its memory is reused
+        Ok(unsafe { core::pin::Pin::new_unchecked(value) })
     }
 
-    /// Allocates space for an instance of `T`, and initializes it with the given closure.
+    /// Allocates an object that can refer to itself, initializing it with the value returned by
+    /// the closure.
+    ///
+    /// Like [`Rc::new_cyclic`](alloc::rc::Rc::new_cyclic), the closure receives an [`ArenaRef`]
+    /// to the object's final address, which can be stored within the object, but can only be
+    /// dereferenced once the closure returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the object could not be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::{prelude::*, ArenaRef};
+    ///
+    /// struct Node<'a> {
+    ///     name: &'static str,
+    ///     parent: ArenaRef<'a, Node<'a>>,
+    /// }
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    ///
+    /// // The root node is its own parent
+    /// let root = allocator.alloc_cyclic(|this: ArenaRef<Node>| {
+    ///     assert!(this.get().is_none());
+    ///     Node { name: "root", parent: this }
+    /// });
+    ///
+    /// let child = allocator.alloc(Node { name: "child", parent: root.parent });
+    /// assert_eq!(child.parent.name, "root");
+    /// assert!(core::ptr::eq(root.parent.get().unwrap(), root));
+    /// ```
     #[inline(always)]
-    fn alloc_with<T, F: FnOnce() -> T>(&'me self, f: F) -> &'a mut T {
-        self.alloc_uninit::<T>().write(f())
+    fn alloc_cyclic<T, F: FnOnce(ArenaRef<'a, T>) -> T>(&'me self, f: F) -> &'a T {
+        self.try_alloc_cyclic(f).unwrap()
     }
 
-    /// Allocates space for an instance of `T`, and moves the value into the allocation.
+    /// Attempts to allocate an object that can refer to itself, initializing it with the value
+    /// returned by the closure.
+    ///
+    /// The closure is only called if the allocation succeeds. See
+    /// [`alloc_cyclic`](Bump::alloc_cyclic) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
     #[inline(always)]
-    fn alloc<T>(&'me self, value: T) -> &'a mut T {
-        self.alloc_with(|| value)
+    fn try_alloc_cyclic<T, F: FnOnce(ArenaRef<'a, T>) -> T>(
+        &'me self,
+        f: F,
+    ) -> Result<&'a T, AllocError> {
+        Ok(self.try_alloc(crate::arena_ref::Slot::new())?.initialize(f))
     }
 
     /// Allocates space for a slice of `T` with the given `length`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the slice overflows, or if any calls to an underlying memory
+    /// allocator fail.
     #[inline(always)]
     fn alloc_slice_uninit<T>(&'me self, length: usize) -> &'a mut [MaybeUninit<T>] {
-        let allocation = self.alloc_with_layout(Layout::array::<T>(length).unwrap());
+        self.try_alloc_slice_uninit(length).unwrap()
+    }
+
+    /// Attempts to allocate space for a slice of `T` with the given `length`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the size of the slice overflows, or if any calls to an
+    /// underlying memory allocator fail.
+    #[inline(always)]
+    fn try_alloc_slice_uninit<T>(
+        &'me self,
+        length: usize,
+    ) -> Result<&'a mut [MaybeUninit<T>], AllocError> {
+        let allocation = self.try_alloc_with_layout(slice_layout::<T>(length)?)?;
 
         // Safety: layout ensures length is valid, allocation is a valid pointer
-        unsafe {
+        Ok(unsafe {
             core::slice::from_raw_parts_mut::<'a, _>(
                 allocation.cast::<MaybeUninit<T>>().as_ptr(),
                 length,
             )
-        }
+        })
     }
 
     /// Allocates space to store the given slice, and copies the slice into the arena.
     #[inline(always)]
     fn alloc_slice<T: Copy>(&'me self, slice: &[T]) -> &'a mut [T] {
-        let destination = self.alloc_slice_uninit::<T>(slice.len());
+        self.try_alloc_slice(slice).unwrap()
+    }
+
+    /// Attempts to allocate space to store the given slice, and copies the slice into the arena.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
+    #[inline(always)]
+    fn try_alloc_slice<T: Copy>(&'me self, slice: &[T]) -> Result<&'a mut [T], AllocError> {
+        let destination = self.try_alloc_slice_uninit::<T>(slice.len())?;
 
         // Safety: [T] and [MaybeUninit<T>] have the same layout
-        unsafe {
+        Ok(unsafe {
             let source: &[MaybeUninit<T>] = core::mem::transmute::<&[T], _>(slice);
 
             destination.copy_from_slice(source);
 
-            core::mem::transmute::<_, &'a mut [T]>(destination)
-        }
+            core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination)
+        })
     }
 
     /// Allocates space to store a string, and copies it into the arena.
     #[inline(always)]
     fn alloc_str(&'me self, s: &str) -> &'a mut str {
-        let bytes = self.alloc_slice(s.as_bytes());
+        self.try_alloc_str(s).unwrap()
+    }
+
+    /// Attempts to allocate space to store a string, and copies it into the arena.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
+    #[inline(always)]
+    fn try_alloc_str(&'me self, s: &str) -> Result<&'a mut str, AllocError> {
+        let bytes = self.try_alloc_slice(s.as_bytes())?;
         // Safety: Bytes are already valid UTF-8
-        unsafe { core::str::from_utf8_unchecked_mut(bytes) }
+        Ok(unsafe { core::str::from_utf8_unchecked_mut(bytes) })
+    }
+
+    /// Extends a slice to the given length, filling the new items with clones of the `value`.
+    ///
+    /// The slice is grown in place if it was the most recent allocation, otherwise it is moved
+    /// into a new allocation. If the `new_length` is not larger, the slice is returned unchanged.
+    ///
+    /// See [`Box::grow`](crate::boxed::Box::grow) for a safe version of this method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the new items could not be allocated.
+    ///
+    /// # Safety
+    ///
+    /// The items are moved out of the `slice`, so the caller must own them, and must not use the
+    /// original `slice` or any other reference to its items after this call. In particular, the
+    /// `slice` must not have been allocated with [`alloc_with_drop`](Bump::alloc_with_drop), since
+    /// the arena still drops those items.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let numbers = allocator.alloc_slice_fill(3, 1u32);
+    ///
+    /// // Safety: the original slices are not used again
+    /// unsafe {
+    ///     let numbers = allocator.grow_slice(numbers, 5, 2);
+    ///     assert_eq!(numbers, [1, 1, 1, 2, 2]);
+    ///
+    ///     let numbers = allocator.shrink_slice(numbers, 2);
+    ///     assert_eq!(numbers, [1, 1]);
+    /// }
+    /// ```
+    unsafe fn grow_slice<T: Clone>(
+        &'me self,
+        slice: &'a mut [T],
+        new_length: usize,
+        value: T,
+    ) -> &'a mut [T] {
+        // Safety: ensured by caller
+        unsafe { self.try_grow_slice(slice, new_length, value) }.unwrap()
+    }
+
+    /// Attempts to extend a slice to the given length, filling the new items with clones of the
+    /// `value`.
+    ///
+    /// See [`grow_slice`](Bump::grow_slice) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `slice` is left unchanged.
+    ///
+    /// # Safety
+    ///
+    /// See [`grow_slice`](Bump::grow_slice).
+    unsafe fn try_grow_slice<T: Clone>(
+        &'me self,
+        slice: &'a mut [T],
+        new_length: usize,
+        value: T,
+    ) -> Result<&'a mut [T], AllocError> {
+        let length = slice.len();
+        if new_length <= length {
+            return Ok(slice);
+        }
+
+        let old_layout = slice_layout::<T>(length)?;
+        let new_layout = slice_layout::<T>(new_length)?;
+
+        // Safety: slice is valid for the old layout, and is no longer used once it is moved
+        let destination = unsafe {
+            self.try_realloc_with_layout(NonNull::from(slice).cast(), old_layout, new_layout)?
+        }
+        .cast::<T>()
+        .as_ptr();
+
+        for index in length..new_length {
+            // Safety: new layout ensures new items are in bounds
+            unsafe { destination.add(index).write(value.clone()) }
+        }
+
+        // Safety: all items are initialized, allocation lives for 'a
+        Ok(unsafe { core::slice::from_raw_parts_mut(destination, new_length) })
+    }
+
+    /// Shortens a slice to the given length, dropping the removed items.
+    ///
+    /// If the slice was the most recent allocation, then the memory for the removed items is
+    /// reclaimed. If the `new_length` is not smaller, the slice is returned unchanged.
+    ///
+    /// See [`Box::shrink`](crate::boxed::Box::shrink) for a safe version of this method.
+    ///
+    /// # Safety
+    ///
+    /// The removed items are dropped, and the remaining items may be moved, so the caller must
+    /// own the items, and must not use the original `slice` or any other reference to its items
+    /// after this call. In particular, the `slice` must not have been allocated with
+    /// [`alloc_with_drop`](Bump::alloc_with_drop), since the arena still drops those items.
+    unsafe fn shrink_slice<T>(&'me self, slice: &'a mut [T], new_length: usize) -> &'a mut [T] {
+        let length = slice.len();
+        if new_length >= length {
+            return slice;
+        }
+
+        // Safety: caller owns the items, removed items are no longer accessible once the slice
+        // is shortened
+        unsafe { core::ptr::drop_in_place(&mut slice[new_length..]) }
+
+        // Layouts are valid, since they are smaller than the existing slice
+        let old_layout = slice_layout::<T>(length).unwrap();
+        let new_layout = slice_layout::<T>(new_length).unwrap();
+        let pointer = NonNull::from(slice).cast::<u8>();
+
+        // Safety: slice is valid for the old layout, and is no longer used once it is moved
+        let destination = unsafe { self.realloc_last(pointer, old_layout, new_layout) }
+            .unwrap_or(pointer)
+            .cast::<T>()
+            .as_ptr();
+
+        // Safety: first items of the slice are initialized, allocation lives for 'a
+        unsafe { core::slice::from_raw_parts_mut(destination, new_length) }
     }
 
     /// Allocates space to store the given slice, cloning each item into the arena.
     fn alloc_slice_cloned<T: Clone>(&'me self, slice: &[T]) -> &'a mut [T] {
-        let destination = self.alloc_slice_uninit::<T>(slice.len());
+        self.try_alloc_slice_cloned(slice).unwrap()
+    }
+
+    /// Attempts to allocate space to store the given slice, cloning each item into the arena.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail.
+    fn try_alloc_slice_cloned<T: Clone>(&'me self, slice: &[T]) -> Result<&'a mut [T], AllocError> {
+        let destination = self.try_alloc_slice_uninit::<T>(slice.len())?;
 
-        for i in 0..slice.len() {
-            destination[i].write(slice[i].clone());
+        for (item, value) in destination.iter_mut().zip(slice) {
+            item.write(value.clone());
         }
 
         // Safety: [T] and [MaybeUninit<T>] have the same layout, destination is initialized
-        unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination) }
+        Ok(unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination) })
     }
 
     /// Allocates space to store a slice, cloning the given `value` to fill it.
@@ -157,7 +1124,21 @@ pub unsafe trait Bump<'me, 'a>: private::Sealed {
     /// assert_eq!(items, &[42u8, 42, 42]);
     /// ```
     fn alloc_slice_fill<T: Clone>(&'me self, length: usize, value: T) -> &'a mut [T] {
-        let destination = self.alloc_slice_uninit::<T>(length);
+        self.try_alloc_slice_fill(length, value).unwrap()
+    }
+
+    /// Attempts to allocate space to store a slice, cloning the given `value` to fill it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the size of the slice overflows, or if any calls to an
+    /// underlying memory allocator fail.
+    fn try_alloc_slice_fill<T: Clone>(
+        &'me self,
+        length: usize,
+        value: T,
+    ) -> Result<&'a mut [T], AllocError> {
+        let destination = self.try_alloc_slice_uninit::<T>(length)?;
         if let Some((last, head)) = destination.split_last_mut() {
             for item in head.iter_mut() {
                 item.write(value.clone());
@@ -166,129 +1147,480 @@ pub unsafe trait Bump<'me, 'a>: private::Sealed {
             last.write(value);
 
             // Safety: [T] and [MaybeUninit<T>] have the same layout, destination is initialized
-            unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination) }
+            Ok(unsafe {
+                core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination)
+            })
         } else {
-            Default::default()
+            Ok(Default::default())
         }
     }
 
     /// Allocates a slice to contain the items yielded by the iterator.
     ///
+    /// If the iterator yields more or fewer items than its length, the slice is resized to fit
+    /// them, see [`alloc_slice_from_iter_unsized`](Bump::alloc_slice_from_iter_unsized).
+    ///
     /// # Panics
     ///
-    /// Panics if enough memory to contain the slice could not be allocated, or if the iterator
-    /// yielded more items than it said it would.
+    /// Panics if enough memory to contain the slice could not be allocated.
     fn alloc_slice_from_iter<T, I>(&'me self, items: I) -> &'a mut [T]
     where
         I: IntoIterator<Item = T>,
         I::IntoIter: ExactSizeIterator,
     {
-        let items_iter = items.into_iter();
-        let destination = self.alloc_slice_uninit::<I::Item>(items_iter.len());
-
-        let mut actual_length = 0usize;
-        let mut destination_iter = destination.iter_mut();
-        for value in items_iter {
-            destination_iter
-                .next()
-                .expect("iterator yielded too many items")
-                .write(value);
-            actual_length += 1;
+        self.try_alloc_slice_from_iter(items).unwrap()
+    }
+
+    /// Attempts to allocate a slice to contain the items yielded by the iterator.
+    ///
+    /// See [`alloc_slice_from_iter`](Bump::alloc_slice_from_iter) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the size of the slice overflows, or if any calls to an
+    /// underlying memory allocator fail, in which case the items that were already yielded are
+    /// dropped.
+    fn try_alloc_slice_from_iter<T, I>(&'me self, items: I) -> Result<&'a mut [T], AllocError>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let items = items.into_iter();
+        let capacity = items.len();
+        let destination = NonNull::from(self.try_alloc_slice_uninit::<T>(capacity)?).cast::<T>();
+
+        // Safety: destination was just allocated with room for the items
+        match unsafe {
+            try_fill_slice(self, destination, capacity, items.map(Ok::<T, Infallible>))
+        }? {
+            Ok(slice) => Ok(slice),
+            Err(never) => match never {},
         }
+    }
+
+    /// Allocates a slice to contain the items yielded by an iterator whose length is not known in
+    /// advance, such as one using [`filter`](Iterator::filter) or
+    /// [`flat_map`](Iterator::flat_map).
+    ///
+    /// The slice is grown in place while it is the most recent allocation, and is moved if the
+    /// current chunk runs out of space or if the iterator itself allocates in the arena. Once the
+    /// iterator is exhausted, any unused space at the end of the slice is reclaimed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if enough memory to contain the slice could not be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let words = ["one two", "", "three"];
+    /// let words = allocator.alloc_slice_from_iter_unsized(words.iter().flat_map(|s| s.split(' ')));
+    /// assert_eq!(words, ["one", "two", "", "three"]);
+    ///
+    /// let evens = allocator.alloc_slice_from_iter_unsized((0..10).filter(|i| i % 2 == 0));
+    /// assert_eq!(evens, [0, 2, 4, 6, 8]);
+    /// ```
+    fn alloc_slice_from_iter_unsized<T, I>(&'me self, items: I) -> &'a mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.try_alloc_slice_from_iter_unsized(items).unwrap()
+    }
+
+    /// Attempts to allocate a slice to contain the items yielded by an iterator whose length is
+    /// not known in advance.
+    ///
+    /// See [`alloc_slice_from_iter_unsized`](Bump::alloc_slice_from_iter_unsized) for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the size of the slice overflows, or if any calls to an
+    /// underlying memory allocator fail, in which case the items that were already yielded are
+    /// dropped.
+    fn try_alloc_slice_from_iter_unsized<T, I>(
+        &'me self,
+        items: I,
+    ) -> Result<&'a mut [T], AllocError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let items = items.into_iter();
+        let capacity = if core::mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            items.size_hint().0.max(4)
+        };
 
-        // If iterator "lies" and returns too few items, then slice
-        let slice = &mut destination[0..actual_length];
+        let destination = NonNull::from(self.try_alloc_slice_uninit::<T>(capacity)?).cast::<T>();
 
-        // Safety: [T] and [MaybeUninit<T>] have the same layout, slice is initialized
-        unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(slice) }
+        // Safety: destination was just allocated with room for the items
+        match unsafe {
+            try_fill_slice(self, destination, capacity, items.map(Ok::<T, Infallible>))
+        }? {
+            Ok(slice) => Ok(slice),
+            Err(never) => match never {},
+        }
     }
 
     /// Allocates a slice of the specified `length`, passing indices to a closure to obtain values to
     /// fill the slice.
-    fn alloc_slice_with<T, F: FnMut(usize) -> T>(
+    fn alloc_slice_with<T, F: FnMut(usize) -> T>(&'me self, length: usize, f: F) -> &'a mut [T] {
+        self.try_alloc_slice_with(length, f).unwrap()
+    }
+
+    /// Attempts to allocate a slice of the specified `length`, passing indices to a closure to
+    /// obtain values to fill the slice.
+    ///
+    /// The closure is only called if the allocation succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the size of the slice overflows, or if any calls to an
+    /// underlying memory allocator fail.
+    fn try_alloc_slice_with<T, F: FnMut(usize) -> T>(
         &'me self,
         length: usize,
         mut f: F,
-    ) -> &'a mut [T] {
-        let destination = self.alloc_slice_uninit::<T>(length);
+    ) -> Result<&'a mut [T], AllocError> {
+        let destination = self.try_alloc_slice_uninit::<T>(length)?;
 
         for (i, item) in destination.iter_mut().enumerate() {
             item.write(f(i));
         }
 
         // Safety: [T] and [MaybeUninit<T>] have the same layout, destination is initialized
-        unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination) }
+        Ok(unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination) })
     }
 
-    /// Allocates a slice of the specified `length`, using a closure to attempt to obtain values to fill the slice.
+    /// Allocates a slice of the specified `length`, using a closure to attempt to obtain values to
+    /// fill the slice.
     ///
-    /// The closure receives the index of the item, and may return [`None`] or [`Err`] to deallocate the slice.
-    fn alloc_slice_try_with<T, E, F>(&'me self, length: usize, mut f: F) -> Result<&'a mut [T], E>
+    /// The closure receives the index of the item, and may return [`Err`] to deallocate the
+    /// slice, in which case the items that were already obtained are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the slice overflows, or if any calls to an underlying memory
+    /// allocator fail. See [`try_alloc_slice_try_with`](Bump::try_alloc_slice_try_with) for a
+    /// version that returns an error instead.
+    fn alloc_slice_try_with<T, E, F>(&'me self, length: usize, f: F) -> Result<&'a mut [T], E>
     where
         F: FnMut(usize) -> Result<T, E>,
     {
-        let attempt = move |allocation: NonNull<u8>| -> Result<&'a mut [T], E> {
-            // Safety: layout ensures length is valid, allocation is a valid pointer
-            let destination = unsafe {
-                core::slice::from_raw_parts_mut::<'a, _>(
-                    allocation.cast::<MaybeUninit<T>>().as_ptr(),
-                    length,
-                )
-            };
+        let destination = NonNull::from(self.alloc_slice_uninit::<T>(length)).cast::<T>();
 
-            for (i, item) in destination.iter_mut().enumerate() {
-                item.write(f(i)?);
-            }
+        // Safety: destination was just allocated with room for the items
+        unsafe { try_fill_slice(self, destination, length, (0..length).map(f)) }.unwrap()
+    }
 
-            // Safety: [T] and [MaybeUninit<T>] have the same layout, destination is fully initialized
-            Ok(unsafe {
-                core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(destination)
-            })
-        };
+    /// Attempts to allocate a slice of the specified `length`, using a closure to attempt to obtain
+    /// values to fill the slice.
+    ///
+    /// See [`alloc_slice_try_with`](Bump::alloc_slice_try_with) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error converted from an [`AllocError`] if the size of the slice overflows, or if
+    /// any calls to an underlying memory allocator fail. Otherwise, returns the first error
+    /// returned by the closure.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::prelude::*;
+    /// use bumpercar::AllocError;
+    ///
+    /// let mut arena = Arena::new();
+    /// let allocator = arena.allocator();
+    /// let result = allocator.try_alloc_slice_try_with(usize::MAX, |i| Ok::<_, AllocError>(i));
+    /// assert_eq!(result, Err(AllocError::LayoutTooLarge));
+    /// ```
+    fn try_alloc_slice_try_with<T, E, F>(&'me self, length: usize, f: F) -> Result<&'a mut [T], E>
+    where
+        E: From<AllocError>,
+        F: FnMut(usize) -> Result<T, E>,
+    {
+        let destination = NonNull::from(self.try_alloc_slice_uninit::<T>(length)?).cast::<T>();
 
-        // Safety: Closure error does not cause dangling pointers
-        unsafe { self.alloc_try_with_layout(Layout::array::<T>(length).unwrap(), attempt) }
+        // Safety: destination was just allocated with room for the items
+        unsafe { try_fill_slice(self, destination, length, (0..length).map(f)) }?
     }
 
     /// Allocates a slice to contain the items yielded by an iterator that may fail early.
     ///
+    /// If an item is an [`Err`], then the slice is deallocated, and the items that were already
+    /// yielded are dropped. If the iterator yields more or fewer items than its length, the slice
+    /// is resized to fit them.
+    ///
     /// # Panics
     ///
-    /// See the documentation for [`Bump::alloc_slice_from_iter`](Bump::alloc_slice_from_iter) for more information.
+    /// Panics if the size of the slice overflows, or if any calls to an underlying memory
+    /// allocator fail. See [`try_alloc_slice_try_from_iter`](Bump::try_alloc_slice_try_from_iter)
+    /// for a version that returns an error instead.
     fn alloc_slice_try_from_iter<T, E, I>(&'me self, items: I) -> Result<&'a mut [T], E>
     where
         I: IntoIterator<Item = Result<T, E>>,
         I::IntoIter: ExactSizeIterator,
     {
-        let items_iter = items.into_iter();
-        let layout = Layout::array::<T>(items_iter.len()).unwrap();
-        let attempt = move |allocation: NonNull<u8>| -> Result<&'a mut [T], E> {
-            // Safety: layout ensures length is valid, allocation is a valid pointer
-            let destination = unsafe {
-                core::slice::from_raw_parts_mut::<'a, _>(
-                    allocation.cast::<MaybeUninit<T>>().as_ptr(),
-                    items_iter.len(),
+        let items = items.into_iter();
+        let capacity = items.len();
+        let destination = NonNull::from(self.alloc_slice_uninit::<T>(capacity)).cast::<T>();
+
+        // Safety: destination was just allocated with room for the items
+        unsafe { try_fill_slice(self, destination, capacity, items) }.unwrap()
+    }
+
+    /// Attempts to allocate a slice to contain the items yielded by an iterator that may fail
+    /// early.
+    ///
+    /// See [`alloc_slice_try_from_iter`](Bump::alloc_slice_try_from_iter) for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error converted from an [`AllocError`] if the size of the slice overflows, or if
+    /// any calls to an underlying memory allocator fail. Otherwise, returns the first error
+    /// yielded by the iterator.
+    fn try_alloc_slice_try_from_iter<T, E, I>(&'me self, items: I) -> Result<&'a mut [T], E>
+    where
+        E: From<AllocError>,
+        I: IntoIterator<Item = Result<T, E>>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let items = items.into_iter();
+        let capacity = items.len();
+        let destination = NonNull::from(self.try_alloc_slice_uninit::<T>(capacity)?).cast::<T>();
+
+        // Safety: destination was just allocated with room for the items
+        unsafe { try_fill_slice(self, destination, capacity, items) }?
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn try_alloc_reports_layout_errors() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        assert_eq!(
+            allocator
+                .try_alloc_slice_uninit::<u32>(usize::MAX / 2)
+                .err(),
+            Some(crate::AllocError::LayoutTooLarge)
+        );
+        assert_eq!(
+            allocator
+                .try_alloc_with_layout(
+                    core::alloc::Layout::from_size_align(isize::MAX as usize - 8, 8).unwrap()
+                )
+                .err(),
+            Some(crate::AllocError::LayoutTooLarge)
+        );
+        assert_eq!(allocator.try_alloc_str("hello").as_deref(), Ok("hello"));
+        assert_eq!(
+            allocator
+                .try_alloc_slice_try_from_iter::<usize, crate::AllocError, _>(
+                    (0..usize::MAX / 2).map(Ok)
                 )
-            };
-
-            let mut actual_length = 0usize;
-            let mut destination_iter = destination.iter_mut();
-            for value in items_iter {
-                destination_iter
-                    .next()
-                    .expect("iterator yielded too many items")
-                    .write(value?);
-                actual_length += 1;
+                .err(),
+            Some(crate::AllocError::LayoutTooLarge)
+        );
+    }
+
+    #[test]
+    fn slice_from_iter_tolerates_wrong_lengths() {
+        struct Lying {
+            remaining: usize,
+            reported: usize,
+        }
+
+        impl Iterator for Lying {
+            type Item = usize;
+
+            fn next(&mut self) -> Option<usize> {
+                self.remaining = self.remaining.checked_sub(1)?;
+                Some(self.remaining)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.reported, Some(self.reported))
+            }
+        }
+
+        impl ExactSizeIterator for Lying {}
+
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let more = allocator.alloc_slice_from_iter(Lying {
+            remaining: 10,
+            reported: 3,
+        });
+        assert!(more.iter().copied().eq((0..10).rev()));
+
+        let fewer = allocator.alloc_slice_from_iter(Lying {
+            remaining: 2,
+            reported: 5,
+        });
+        assert_eq!(fewer, [1, 0]);
+    }
+
+    #[test]
+    fn slice_try_from_iter_drops_items_on_error() {
+        let counter = alloc::rc::Rc::new(());
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        allocator.alloc(0u64);
+        let before = allocator.stats();
+        let result = allocator.alloc_slice_try_from_iter((0..5).map(|i| {
+            if i < 3 {
+                Ok(alloc::rc::Rc::clone(&counter))
+            } else {
+                Err(i)
             }
+        }));
+
+        assert_eq!(result.err(), Some(3));
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+        assert_eq!(allocator.stats(), before);
+    }
+
+    #[test]
+    fn failed_slices_are_deallocated() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let marker: *const u8 = allocator.alloc(0u8);
+        let stats = allocator.stats();
+
+        let result =
+            allocator.alloc_slice_try_with(128, |i| if i < 100 { Ok(i as u8) } else { Err(i) });
+        assert_eq!(result.err(), Some(100));
+        let result = allocator.try_alloc_slice_try_from_iter((0..128u8).map(|i| match i {
+            0..=99 => Ok(i),
+            _ => Err(crate::AllocError::OutOfMemory),
+        }));
+        assert_eq!(result.err(), Some(crate::AllocError::OutOfMemory));
+        assert_eq!(allocator.stats(), stats);
+
+        // The next allocation is placed right after the marker, as if the slices never existed
+        let next: *const u8 = allocator.alloc(1u8);
+        assert_eq!(next, marker.wrapping_sub(1));
+    }
+
+    #[test]
+    fn grow_slice_in_place_or_by_copying() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let numbers = allocator.alloc_slice_fill(4, 7u64);
+        let end = numbers.as_ptr_range().end;
+
+        // Safety: the original slices are not used again
+        let numbers = unsafe { allocator.grow_slice(numbers, 16, 8) };
+        assert_eq!(numbers.as_ptr_range().end, end);
+        assert_eq!(numbers[..4], [7; 4]);
+        assert_eq!(numbers[4..], [8; 12]);
 
-            // If iterator "lies" and returns too few items, then slice
-            let slice = &mut destination[0..actual_length];
+        // Not the most recent allocation, so it has to be copied
+        allocator.alloc(0u8);
+        // Safety: the original slice is not used again
+        let numbers = unsafe { allocator.grow_slice(numbers, 20, 9) };
+        assert_ne!(numbers.as_ptr_range().end, end);
+        assert_eq!(numbers[..4], [7; 4]);
+        assert_eq!(numbers[16..], [9; 4]);
 
-            // Safety: [T] and [MaybeUninit<T>] have the same layout, slice is initialized
-            Ok(unsafe { core::mem::transmute::<&'a mut [MaybeUninit<T>], &'a mut [T]>(slice) })
+        // Copies into a new chunk if the current one is full
+        // Safety: the original slice is not used again
+        let numbers = unsafe { allocator.grow_slice(numbers, 4096, 10) };
+        assert_eq!(numbers[4095], 10);
+        assert_eq!(allocator.stats().chunk_count, 2);
+    }
+
+    #[test]
+    fn shrink_slice_reclaims_memory() {
+        use alloc::rc::Rc;
+
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let shared = Rc::new(());
+        let items = allocator.alloc_slice_fill(8, shared.clone());
+        let before = allocator.stats();
+
+        // Safety: the items are owned by the slice, which is not used again
+        let items = unsafe { allocator.shrink_slice(items, 3) };
+        assert_eq!(Rc::strong_count(&shared), 4);
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            allocator.stats().allocated_bytes,
+            before.allocated_bytes - 5 * core::mem::size_of::<Rc<()>>()
+        );
+
+        // Safety: pointer and layout refer to the remaining items
+        let moved = unsafe {
+            allocator.realloc_last(
+                core::ptr::NonNull::from(&mut *items).cast(),
+                core::alloc::Layout::for_value(items),
+                core::alloc::Layout::new::<[Rc<()>; 5]>(),
+            )
         };
+        assert!(moved.is_some());
+    }
+
+    #[test]
+    fn unsized_iterators_are_collected() {
+        let mut arena = crate::ArenaBuilder::new().initial_chunk_size(256).build();
+        let allocator = arena.allocator();
+
+        // Grows past the end of the first chunk
+        let evens = allocator.alloc_slice_from_iter_unsized((0..1000u32).filter(|i| i % 2 == 0));
+        assert_eq!(evens.len(), 500);
+        assert!(evens.iter().copied().eq((0..1000).step_by(2)));
+        assert!(allocator.stats().chunk_count > 1);
+
+        // Iterator allocates in the arena, so the slice is moved instead
+        use alloc::string::ToString;
+        let words = allocator.alloc_slice_from_iter_unsized(
+            (0..20)
+                .filter(|i| i % 3 != 0)
+                .map(|i| &*allocator.alloc_str(&i.to_string())),
+        );
+        assert_eq!(words.len(), 13);
+        assert_eq!(words[..3], ["1", "2", "4"]);
+
+        let before = allocator.stats();
+        let empty = allocator.alloc_slice_from_iter_unsized(core::iter::empty::<u64>());
+        assert!(empty.is_empty());
+        assert_eq!(allocator.stats().allocated_bytes, before.allocated_bytes);
+
+        let units = allocator.alloc_slice_from_iter_unsized((0..50).map(|_| ()));
+        assert_eq!(units.len(), 50);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn drops_run_in_reverse_order() {
+        use crate::testing::{DropLog, Record};
+
+        let dropped = DropLog::default();
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        allocator.alloc_with_drop(Record::new(1, &dropped));
+        allocator.with_frame(|frame| {
+            frame.alloc_with_drop(Record::new(2, &dropped));
+            frame.alloc_with_drop(Record::new(3, &dropped));
+        });
+        assert_eq!(*dropped.lock().unwrap(), [3, 2]);
+
+        allocator.alloc_with_drop(Record::new(4, &dropped));
+        arena.reset();
+        assert_eq!(*dropped.lock().unwrap(), [3, 2, 4, 1]);
 
-        // Safety: Closure error does not cause dangling pointers
-        unsafe { self.alloc_try_with_layout(layout, attempt) }
+        arena.allocator().alloc_with_drop(Record::new(5, &dropped));
+        core::mem::drop(arena);
+        assert_eq!(*dropped.lock().unwrap(), [3, 2, 4, 1, 5]);
     }
 }
@@ -0,0 +1,167 @@
+use crate::raw_arena::{RawArena, RawArenaState};
+use crate::{ChunkAllocator, Global};
+
+/// A bump allocator that frees all of its allocations when it is dropped, or when
+/// [`rollback`](Checkpoint::rollback) is called.
+///
+/// This provides the same behavior as a [`Frame`](crate::Frame), but without the need for a
+/// closure. Objects allocated with a [`Checkpoint`] borrow the [`Checkpoint`] itself, so they
+/// cannot be used after the arena is rolled back.
+///
+/// # Examples
+///
+/// ```
+/// use bumpercar::prelude::*;
+///
+/// let mut arena = Arena::new();
+/// let mut allocator = arena.allocator();
+/// let kept = allocator.alloc(1u32);
+/// let before = allocator.stats();
+///
+/// let checkpoint = allocator.checkpoint();
+/// let temporary = checkpoint.alloc_slice_fill(100, 0u8);
+/// assert_eq!(temporary.len(), 100);
+/// checkpoint.rollback();
+///
+/// assert_eq!(allocator.stats(), before);
+/// assert_eq!(*kept, 1);
+/// ```
+///
+/// ```compile_fail
+/// use bumpercar::prelude::*;
+///
+/// let mut arena = Arena::new();
+/// let mut allocator = arena.allocator();
+/// let checkpoint = allocator.checkpoint();
+/// let temporary = checkpoint.alloc(5u32);
+/// // Does not compile, the allocation cannot be used after the rollback
+/// checkpoint.rollback();
+/// println!("{temporary}");
+/// ```
+#[derive(Debug)]
+pub struct Checkpoint<'a, A: ChunkAllocator = Global> {
+    arena: &'a mut RawArena<A>,
+    state: Option<RawArenaState>,
+}
+
+impl<'a, A: ChunkAllocator> Checkpoint<'a, A> {
+    pub(crate) fn in_arena(arena: &'a mut RawArena<A>) -> Self {
+        Self {
+            state: arena.current_state(),
+            arena,
+        }
+    }
+
+    pub(crate) fn arena_mut(&mut self) -> &mut RawArena<A> {
+        self.arena
+    }
+
+    /// Frees all objects allocated since the [`Checkpoint`] was created.
+    ///
+    /// This is equivalent to dropping the [`Checkpoint`].
+    pub fn rollback(self) {}
+
+    /// Creates a nested [`Checkpoint`], which rolls back to the current state of this
+    /// [`Checkpoint`].
+    pub fn checkpoint(&mut self) -> Checkpoint<'_, A> {
+        Checkpoint::in_arena(self.arena)
+    }
+
+    /// Returns a snapshot of the underlying arena's memory usage, including allocations made
+    /// before the [`Checkpoint`] was created.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Returns an iterator describing each of the arena's chunks, from the first chunk to the
+    /// last.
+    ///
+    /// See [`ChunkInfo`](crate::ChunkInfo) for more information.
+    pub fn chunks(&self) -> crate::ArenaChunks<'_> {
+        self.arena.chunk_infos()
+    }
+}
+
+impl<A: ChunkAllocator> Drop for Checkpoint<'_, A> {
+    fn drop(&mut self) {
+        // Safety: allocations borrow the checkpoint, so none can outlive it. Nested checkpoints
+        // and frames borrow it mutably, so they are restored first.
+        unsafe {
+            self.arena.restore_state(self.state);
+        }
+    }
+}
+
+impl<A: ChunkAllocator> crate::bump::private::Sealed for Checkpoint<'_, A> {
+    #[inline(always)]
+    fn current_finger(&self) -> Option<&core::sync::atomic::AtomicPtr<u8>> {
+        self.arena.current_finger()
+    }
+}
+
+// Safety: allocations live only as long as the checkpoint is borrowed, and the checkpoint is
+// only rolled back once it is no longer borrowed
+unsafe impl<'me, 'a: 'me, A: ChunkAllocator> crate::Bump<'me, 'me> for Checkpoint<'a, A> {
+    type ChunkAllocator = A;
+
+    crate::bump::forward_bump_methods!('me, 'me);
+}
+
+#[cfg(feature = "allocator-api2")]
+// Safety: allocations live until the checkpoint is rolled back, which cannot happen while it is
+// borrowed
+crate::allocator_api::impl_allocator!([A: ChunkAllocator] Checkpoint<'_, A>);
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn drop_restores_state_after_early_return() {
+        fn fill(allocator: &mut Allocator<'_>, count: usize) -> Option<usize> {
+            let checkpoint = allocator.checkpoint();
+            let values = checkpoint.alloc_slice_fill(count, 7u64);
+            if count > 1000 {
+                return None;
+            }
+            Some(values.iter().map(|value| *value as usize).sum())
+        }
+
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let kept = allocator.alloc(3u8);
+        let before = allocator.stats();
+
+        assert_eq!(fill(&mut allocator, 10), Some(70));
+        assert_eq!(fill(&mut allocator, 10_000), None);
+        assert_eq!(allocator.stats().allocated_bytes, before.allocated_bytes);
+        assert_eq!(*kept, 3);
+    }
+
+    #[test]
+    fn nested_checkpoints_roll_back_in_order() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let mut outer = allocator.checkpoint();
+        let start = outer.stats().allocated_bytes;
+
+        {
+            let outer_value = outer.alloc(1u32) as *const u32;
+            let middle = outer.stats().allocated_bytes;
+            let mut inner = outer.checkpoint();
+            inner.alloc_slice_fill(8192, 0u8);
+            inner.with_frame(|frame| {
+                frame.alloc(2u64);
+            });
+            drop(inner);
+
+            assert_eq!(outer.stats().allocated_bytes, middle);
+            assert_eq!(outer.alloc(1u32) as *const u32, outer_value.wrapping_sub(1));
+        }
+
+        outer.rollback();
+        assert_eq!(allocator.stats().allocated_bytes, start);
+    }
+}
@@ -0,0 +1,116 @@
+//! Contains the [`ChunkAllocator`] trait.
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+/// Provides the memory for the chunks that an arena bump allocates objects into.
+///
+/// When the `allocator-api2` feature is enabled, this trait is implemented for all types that
+/// implement [`allocator_api2::alloc::Allocator`].
+///
+/// # Safety
+///
+/// Pointers returned by [`allocate_chunk`](ChunkAllocator::allocate_chunk) must point to a block
+/// of memory that is valid for reads and writes of `layout.size()` bytes, is aligned to
+/// `layout.align()`, and remains valid until it is passed to
+/// [`deallocate_chunk`](ChunkAllocator::deallocate_chunk), or until the allocator is dropped.
+pub unsafe trait ChunkAllocator {
+    /// Allocates a block of memory with the given [`Layout`], returning [`None`] if the memory
+    /// could not be allocated.
+    ///
+    /// The size of the `layout` is never zero.
+    fn allocate_chunk(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// Deallocates a block of memory.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must have been returned by an earlier call to
+    /// [`allocate_chunk`](ChunkAllocator::allocate_chunk) on `self` with the same `layout`, and
+    /// must not have already been deallocated.
+    unsafe fn deallocate_chunk(&self, pointer: NonNull<u8>, layout: Layout);
+}
+
+/// Allocates chunks using the global memory allocator.
+///
+/// This is the default [`ChunkAllocator`] used by arenas.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Global;
+
+// Safety: memory returned by global allocator is valid until it is deallocated
+unsafe impl ChunkAllocator for Global {
+    #[inline(always)]
+    fn allocate_chunk(&self, layout: Layout) -> Option<NonNull<u8>> {
+        // Safety: layout size is never 0
+        NonNull::new(unsafe { alloc::alloc::alloc(layout) })
+    }
+
+    #[inline(always)]
+    unsafe fn deallocate_chunk(&self, pointer: NonNull<u8>, layout: Layout) {
+        // Safety: ensured by caller
+        unsafe { alloc::alloc::dealloc(pointer.as_ptr(), layout) }
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+// Safety: Allocator trait has the same requirements regarding the validity of memory blocks
+unsafe impl<A: allocator_api2::alloc::Allocator + ?Sized> ChunkAllocator for A {
+    #[inline(always)]
+    fn allocate_chunk(&self, layout: Layout) -> Option<NonNull<u8>> {
+        self.allocate(layout).ok().map(|block| block.cast())
+    }
+
+    #[inline(always)]
+    unsafe fn deallocate_chunk(&self, pointer: NonNull<u8>, layout: Layout) {
+        // Safety: ensured by caller
+        unsafe { self.deallocate(pointer, layout) }
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn custom_chunk_allocator_is_used() {
+        use core::cell::Cell;
+
+        #[derive(Default)]
+        struct Counting {
+            allocated: Cell<usize>,
+            deallocated: Cell<usize>,
+        }
+
+        // Safety: delegates to the global allocator
+        unsafe impl crate::ChunkAllocator for &Counting {
+            fn allocate_chunk(
+                &self,
+                layout: core::alloc::Layout,
+            ) -> Option<core::ptr::NonNull<u8>> {
+                self.allocated.set(self.allocated.get() + 1);
+                crate::Global.allocate_chunk(layout)
+            }
+
+            unsafe fn deallocate_chunk(
+                &self,
+                pointer: core::ptr::NonNull<u8>,
+                layout: core::alloc::Layout,
+            ) {
+                self.deallocated.set(self.deallocated.get() + 1);
+                // Safety: ensured by caller
+                unsafe { crate::Global.deallocate_chunk(pointer, layout) }
+            }
+        }
+
+        let counter = Counting::default();
+        let mut arena = Arena::new_in(&counter);
+        let mut allocator = arena.allocator();
+        allocator.alloc_slice_fill(4096, 1u8);
+        allocator.with_frame(|frame| {
+            frame.alloc_slice_fill(65536, 2u8);
+        });
+        assert_eq!(counter.allocated.get(), 2);
+        core::mem::drop(arena);
+        assert_eq!(counter.deallocated.get(), 2);
+    }
+}
@@ -0,0 +1,251 @@
+//! Contains the [`CloneIn`] trait.
+
+use crate::boxed::Box;
+use crate::collections::{String, Vec};
+use crate::Bump;
+
+/// Clones a value into an arena, producing an equivalent value whose references point into the
+/// arena instead.
+///
+/// This is used to keep values built within a [`KeepFrame`](crate::KeepFrame) after the frame
+/// ends, see [`Bump::with_frame_keep`] for more information.
+///
+/// Implementations are provided for primitives, references, slices, [`str`], [`Option`],
+/// tuples of up to 8 items, and the arena's own [`Box`](crate::boxed::Box),
+/// [`Vec`](crate::collections::Vec) and [`String`](crate::collections::String). Since the
+/// collections need an allocator that lives as long as them, a [`Vec`](crate::collections::Vec)
+/// is cloned into a slice, and a [`String`](crate::collections::String) into a [`str`].
+///
+/// There is no derive macro, but implementing [`CloneIn`] for a struct or enum only requires
+/// cloning each of its fields, as shown below.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{Bump, CloneIn};
+///
+/// struct Token<'a> {
+///     text: &'a str,
+///     line: u32,
+/// }
+///
+/// impl<'p> CloneIn<'p> for Token<'_> {
+///     type Cloned = Token<'p>;
+///
+///     fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Token<'p> {
+///         Token {
+///             text: self.text.clone_in(bump),
+///             line: self.line,
+///         }
+///     }
+/// }
+/// ```
+pub trait CloneIn<'p> {
+    /// The type of the cloned value, which lives for the lifetime `'p` of the arena's
+    /// allocations.
+    type Cloned: 'p;
+
+    /// Clones the value, allocating anything that it refers to with the given bump allocator.
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned;
+}
+
+macro_rules! clone_in_self {
+    ($($ty:ty),*) => {
+        $(
+            impl<'p> CloneIn<'p> for $ty {
+                type Cloned = Self;
+
+                #[inline(always)]
+                fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, _: &'me B) -> Self {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+clone_in_self!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+impl<'p, T: CloneIn<'p>> CloneIn<'p> for &T {
+    type Cloned = &'p T::Cloned;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+        bump.alloc((**self).clone_in(bump))
+    }
+}
+
+impl<'p> CloneIn<'p> for &str {
+    type Cloned = &'p str;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> &'p str {
+        bump.alloc_str(self)
+    }
+}
+
+impl<'p, T: CloneIn<'p>> CloneIn<'p> for &[T] {
+    type Cloned = &'p [T::Cloned];
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+        bump.alloc_slice_from_iter(self.iter().map(|item| item.clone_in(bump)))
+    }
+}
+
+impl<'p, T: CloneIn<'p>> CloneIn<'p> for Option<T> {
+    type Cloned = Option<T::Cloned>;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+        self.as_ref().map(|value| value.clone_in(bump))
+    }
+}
+
+macro_rules! clone_in_tuple {
+    ($(($($item:ident $index:tt),+)),*) => {
+        $(
+            impl<'p, $($item: CloneIn<'p>),+> CloneIn<'p> for ($($item,)+) {
+                type Cloned = ($($item::Cloned,)+);
+
+                fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+                    ($(self.$index.clone_in(bump),)+)
+                }
+            }
+        )*
+    };
+}
+
+clone_in_tuple!(
+    (T0 0),
+    (T0 0, T1 1),
+    (T0 0, T1 1, T2 2),
+    (T0 0, T1 1, T2 2, T3 3),
+    (T0 0, T1 1, T2 2, T3 3, T4 4),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6),
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7)
+);
+
+impl<'p, T: CloneIn<'p>> CloneIn<'p> for Box<'_, T> {
+    type Cloned = Box<'p, T::Cloned>;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+        Box::with_finger(bump.alloc((**self).clone_in(bump)), bump)
+    }
+}
+
+impl<'p, T: CloneIn<'p>> CloneIn<'p> for Box<'_, [T]> {
+    type Cloned = Box<'p, [T::Cloned]>;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+        let items = bump.alloc_slice_from_iter(self.iter().map(|item| item.clone_in(bump)));
+        Box::with_finger(items, bump)
+    }
+}
+
+impl<'p> CloneIn<'p> for Box<'_, str> {
+    type Cloned = Box<'p, str>;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Box<'p, str> {
+        Box::with_finger(bump.alloc_str(self), bump)
+    }
+}
+
+impl<'p, 'a, T, A> CloneIn<'p> for Vec<'a, T, A>
+where
+    T: CloneIn<'p>,
+    A: Bump<'a, 'a> + ?Sized,
+{
+    type Cloned = &'p [T::Cloned];
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Self::Cloned {
+        self.as_slice().clone_in(bump)
+    }
+}
+
+impl<'p, 'a, A: Bump<'a, 'a> + ?Sized> CloneIn<'p> for String<'a, A> {
+    type Cloned = &'p str;
+
+    fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> &'p str {
+        bump.alloc_str(self)
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::CloneIn;
+    use crate::boxed::Box;
+    use crate::collections::{String, Vec};
+    use crate::prelude::*;
+
+    #[test]
+    fn boxes_are_cloned_into_other_arenas() {
+        let mut source = Arena::new();
+        let mut destination = Arena::new();
+        let allocator = destination.allocator();
+
+        let (numbers, boxed) = {
+            let scratch = source.allocator();
+            let numbers = Box::new_slice(&[1u8, 2, 3], &scratch);
+            let boxed = Box::new((Box::new(5u32, &scratch), 'x', "text"), &scratch);
+            (numbers.clone_in(&allocator), boxed.clone_in(&allocator))
+        };
+
+        source.reset();
+        source.allocator().alloc_slice_fill(4096, 0xFFu8);
+        assert_eq!(*numbers, [1, 2, 3]);
+        assert_eq!((*boxed.0, boxed.1, boxed.2), (5, 'x', "text"));
+    }
+
+    #[test]
+    fn vecs_are_cloned_into_other_arenas() {
+        let mut source = Arena::new();
+        let mut destination = Arena::new();
+        let allocator = destination.allocator();
+
+        let words = {
+            let scratch = source.allocator();
+            let mut words = Vec::new_in(&scratch);
+            words.push("hello");
+            words.push("world");
+            words.clone_in(&allocator)
+        };
+
+        source.reset();
+        source.allocator().alloc_slice_fill(4096, 0xFFu8);
+        assert_eq!(words, ["hello", "world"]);
+    }
+
+    #[test]
+    fn strings_are_cloned_into_other_arenas() {
+        let mut source = Arena::new();
+        let mut destination = Arena::new();
+        let allocator = destination.allocator();
+
+        let text = {
+            let scratch = source.allocator();
+            let mut text = String::from_str_in("hello", &scratch);
+            text.push_str(" world");
+            text.clone_in(&allocator)
+        };
+
+        source.reset();
+        source.allocator().alloc_slice_fill(4096, 0xFFu8);
+        assert_eq!(text, "hello world");
+    }
+}
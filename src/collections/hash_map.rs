@@ -0,0 +1,496 @@
+//! Contains a hash map whose table is stored within an arena.
+//!
+//! # Example
+//!
+//! ```
+//! use bumpercar::prelude::*;
+//! use bumpercar::collections::{hash_map, HashMap};
+//!
+//! let mut arena = Arena::new();
+//! let allocator = arena.allocator();
+//!
+//! let mut symbols = HashMap::new_in(&allocator);
+//! for (index, name) in ["main", "print", "exit"].into_iter().enumerate() {
+//!     symbols.insert(name, index);
+//! }
+//!
+//! let symbols = hash_map::freeze(symbols);
+//! assert_eq!(symbols.get("print"), Some(&1));
+//! ```
+
+use crate::{Allocator, Bump};
+use core::alloc::Layout;
+use core::hash::{BuildHasher, Hash};
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+use hashbrown::raw::RawTable;
+
+#[doc(no_inline)]
+pub use hashbrown::hash_map::DefaultHashBuilder;
+
+/// Memory left behind by the old tables of a hash map or set, which lies directly above its
+/// current table.
+///
+/// A larger table is allocated before the old table is freed, so if the old table was the most
+/// recent allocation, the new table is placed directly below it. The old tables' memory can then
+/// be reclaimed once the current table is freed. Small tables may need padding between them
+/// though, in which case the tables above the padding are only reclaimed once the arena is reset.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct OldTables {
+    /// The start and end of the memory used by the old tables.
+    region: Option<(NonNull<u8>, NonNull<u8>)>,
+}
+
+/// A table that was the most recent allocation before it was grown.
+pub(super) struct LastTable {
+    table: NonNull<u8>,
+    end: NonNull<u8>,
+}
+
+impl LastTable {
+    /// Returns the table described by the [`allocation_info`](RawTable::allocation_info) if it is
+    /// the most recent allocation made by the `bump` allocator.
+    pub(super) fn new<'a, B: Bump<'a, 'a>>(
+        bump: &B,
+        (table, layout): (NonNull<u8>, Layout),
+    ) -> Option<Self> {
+        let finger = bump.current_finger()?;
+        if layout.size() == 0 || finger.load(Ordering::Relaxed) != table.as_ptr() {
+            return None;
+        }
+
+        Some(Self {
+            table,
+            // Safety: the table is layout.size() bytes long
+            end: unsafe { NonNull::new_unchecked(table.as_ptr().add(layout.size())) },
+        })
+    }
+}
+
+impl OldTables {
+    /// Keeps track of the old table after a table was grown into the table described by the
+    /// [`allocation_info`](RawTable::allocation_info).
+    ///
+    /// The old table's memory is only kept track of if it was the `last` allocation, and the new
+    /// table ends exactly where it starts. Since chunks do not overlap, this also means that both
+    /// tables are in the same chunk.
+    pub(super) fn grown(
+        &mut self,
+        last: Option<LastTable>,
+        (table, layout): (NonNull<u8>, Layout),
+    ) {
+        // Safety: the table is layout.size() bytes long
+        let start = unsafe { NonNull::new_unchecked(table.as_ptr().add(layout.size())) };
+
+        self.region = last.filter(|old| old.table == start).map(|old| {
+            let end = match self.region {
+                Some((previous_start, previous_end)) if previous_start == old.end => previous_end,
+                _ => old.end,
+            };
+
+            (start, end)
+        });
+    }
+
+    /// Reclaims the memory of the old tables, if the current table was the most recent allocation
+    /// and has already been freed.
+    pub(super) fn reclaim<'a, B: Bump<'a, 'a>>(self, bump: &'a B) {
+        if let Some((start, end)) = self.region {
+            let size = end.as_ptr() as usize - start.as_ptr() as usize;
+
+            // Safety: size of the region does not overflow, since it was allocated
+            let layout = unsafe { Layout::from_size_align_unchecked(size, 1) };
+
+            // Safety: the region is only made up of old tables that were already freed, and it is
+            // only deallocated if nothing else was allocated below it
+            unsafe { bump.dealloc_last(start, layout) };
+        }
+    }
+}
+
+/// A hash map whose table is allocated with a [`Bump`] allocator.
+///
+/// This wraps a [`hashbrown::HashMap`] that allocates with a reference to the [`Bump`]
+/// allocator, and dereferences to it, so the entire [`hashbrown`] API is available. Like other
+/// allocations made by the allocator, the table's memory is reclaimed when the map is dropped if
+/// it is the most recent allocation.
+///
+/// When [`insert`](HashMap::insert), [`entry`](HashMap::entry), [`extend`](Extend::extend) or
+/// [`reserve`](HashMap::reserve) grow the map while its table is the most recent allocation, the
+/// new table is allocated directly below the old one, as long as it fits in the arena's current
+/// chunk. The memory of the old tables is then reclaimed along with the current table when the
+/// map is dropped. Otherwise, or if the map grows through methods of the [`hashbrown::HashMap`]
+/// such as [`try_reserve`](hashbrown::HashMap::try_reserve), or if the map is consumed by
+/// [`into_inner`](HashMap::into_inner), the old tables' memory is only reclaimed once the arena
+/// is reset.
+///
+/// See the [module level documentation](crate::collections::hash_map) for more information.
+pub struct HashMap<'a, K, V, S = DefaultHashBuilder, B = Allocator<'a>>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    map: hashbrown::HashMap<K, V, S, &'a B>,
+    old_tables: OldTables,
+}
+
+impl<'a, K, V, B> HashMap<'a, K, V, DefaultHashBuilder, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    /// Creates an empty [`HashMap`], which does not allocate until entries are inserted.
+    pub fn new_in(bump: &'a B) -> Self {
+        Self::with_hasher_in(DefaultHashBuilder::default(), bump)
+    }
+
+    /// Creates an empty [`HashMap`] with space for at least `capacity` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table could not be allocated.
+    pub fn with_capacity_in(capacity: usize, bump: &'a B) -> Self {
+        Self::with_capacity_and_hasher_in(capacity, DefaultHashBuilder::default(), bump)
+    }
+}
+
+impl<'a, K, V, S, B> HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    /// Creates an empty [`HashMap`] which uses the given `hasher` to hash keys.
+    pub fn with_hasher_in(hasher: S, bump: &'a B) -> Self {
+        Self {
+            map: hashbrown::HashMap::with_hasher_in(hasher, bump),
+            old_tables: OldTables::default(),
+        }
+    }
+
+    /// Creates an empty [`HashMap`] with space for at least `capacity` entries, which uses the
+    /// given `hasher` to hash keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table could not be allocated.
+    pub fn with_capacity_and_hasher_in(capacity: usize, hasher: S, bump: &'a B) -> Self {
+        Self {
+            map: hashbrown::HashMap::with_capacity_and_hasher_in(capacity, hasher, bump),
+            old_tables: OldTables::default(),
+        }
+    }
+
+    /// Returns the allocator used to allocate the [`HashMap`]'s table.
+    #[inline(always)]
+    pub fn bump(&self) -> &'a B {
+        self.map.allocator()
+    }
+
+    /// Consumes the [`HashMap`], returning the underlying [`hashbrown::HashMap`].
+    #[inline(always)]
+    pub fn into_inner(self) -> hashbrown::HashMap<K, V, S, &'a B> {
+        let map = core::mem::ManuallyDrop::new(self);
+
+        // Safety: the map is not used again, since its destructor is not run
+        unsafe { core::ptr::read(&map.map) }
+    }
+}
+
+impl<'a, K, V, S, B> HashMap<'a, K, V, S, B>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    /// Reserves space for at least `additional` more entries.
+    ///
+    /// If the table is the most recent allocation, the memory of the old table is reclaimed when
+    /// the map is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, or if the table could not be allocated.
+    pub fn reserve(&mut self, additional: usize) {
+        if additional <= self.map.capacity() - self.map.len() {
+            return;
+        }
+
+        let bump = self.bump();
+        let last = LastTable::new(bump, self.map.raw_table().allocation_info());
+        self.map.reserve(additional);
+        self.old_tables
+            .grown(last, self.map.raw_table().allocation_info());
+    }
+
+    /// Inserts a key-value pair into the [`HashMap`], returning the old value if the key was
+    /// already present.
+    ///
+    /// See [`hashbrown::HashMap::insert`] for more information.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.is_full() {
+            // The hashbrown::HashMap grows a full table even if the key is already present
+            if let Some(existing) = self.map.get_mut(&key) {
+                return Some(core::mem::replace(existing, value));
+            }
+
+            self.reserve(1);
+        }
+
+        self.map.insert(key, value)
+    }
+
+    /// Gets the entry for the given `key`, for in-place manipulation.
+    ///
+    /// See [`hashbrown::HashMap::entry`] for more information.
+    pub fn entry(&mut self, key: K) -> hashbrown::hash_map::Entry<'_, K, V, S, &'a B> {
+        if self.is_full() && !self.map.contains_key(&key) {
+            self.reserve(1);
+        }
+
+        self.map.entry(key)
+    }
+
+    /// Returns `true` if inserting a new entry would grow the table.
+    fn is_full(&self) -> bool {
+        self.map.len() == self.map.capacity()
+    }
+}
+
+impl<'a, K, V, S, B> Drop for HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    fn drop(&mut self) {
+        let bump = self.bump();
+
+        // Frees the current table first, so that the old tables become the most recent allocation
+        drop(core::mem::replace(
+            self.map.raw_table_mut(),
+            RawTable::new_in(bump),
+        ));
+
+        self.old_tables.reclaim(bump);
+    }
+}
+
+impl<'a, K, V, S, B> core::ops::Deref for HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Target = hashbrown::HashMap<K, V, S, &'a B>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<'a, K, V, S, B> core::ops::DerefMut for HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+
+impl<'a, K, V, S, B> Extend<(K, V)> for HashMap<'a, K, V, S, B>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, entries: I) {
+        let entries = entries.into_iter();
+
+        // Some keys may already be present, see hashbrown::HashMap::extend
+        let additional = if self.is_empty() {
+            entries.size_hint().0
+        } else {
+            (entries.size_hint().0 + 1) / 2
+        };
+
+        self.reserve(additional);
+        for (key, value) in entries {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, 'm, K, V, S, B> IntoIterator for &'m HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Item = (&'m K, &'m V);
+    type IntoIter = hashbrown::hash_map::Iter<'m, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<'a, 'm, K, V, S, B> IntoIterator for &'m mut HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Item = (&'m K, &'m mut V);
+    type IntoIter = hashbrown::hash_map::IterMut<'m, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut()
+    }
+}
+
+impl<'a, K, V, S, B> IntoIterator for HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Item = (K, V);
+    type IntoIter = hashbrown::hash_map::IntoIter<K, V, &'a B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().into_iter()
+    }
+}
+
+impl<'a, K, V, S, B> core::fmt::Debug for HashMap<'a, K, V, S, B>
+where
+    K: core::fmt::Debug,
+    V: core::fmt::Debug,
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.map, f)
+    }
+}
+
+/// Moves the `map` into the arena, returning a read-only reference to it that lives for the
+/// lifetime `'a` of the arena's allocations.
+///
+/// Note that the destructors for the map's keys and values are **never run**.
+pub fn freeze<'a, K, V, S, B>(map: HashMap<'a, K, V, S, B>) -> &'a HashMap<'a, K, V, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    let bump = map.bump();
+    bump.alloc(map)
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::HashMap;
+    use crate::prelude::*;
+    use alloc::{rc::Rc, string::ToString};
+
+    #[test]
+    fn dropping_map_reclaims_table() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        allocator.alloc(0u8);
+        let before = allocator.stats();
+
+        let mut map = HashMap::with_capacity_in(100, &allocator);
+        for i in 0..100u32 {
+            map.insert(i, i * 2);
+        }
+
+        assert_eq!(map.get(&7), Some(&14));
+        assert!(allocator.stats().allocated_bytes > before.allocated_bytes);
+        drop(map);
+        assert_eq!(allocator.stats().allocated_bytes, before.allocated_bytes);
+    }
+
+    #[test]
+    fn dropping_grown_map_reclaims_old_tables() {
+        let mut arena = Arena::with_capacity(1 << 16);
+        let allocator = arena.allocator();
+        allocator.alloc(0u8);
+        let before = allocator.stats().allocated_bytes;
+
+        // Starts out large enough that no padding is needed between tables
+        let mut map = HashMap::with_capacity_in(16, &allocator);
+        for i in 0..1000u32 {
+            map.insert(i, i);
+        }
+
+        assert!((0..1000).all(|i| map.get(&i) == Some(&i)));
+        assert!(allocator.stats().allocated_bytes > before);
+
+        // Old tables lie directly above the current table, so nothing is left behind
+        drop(map);
+        assert_eq!(allocator.stats().allocated_bytes, before);
+    }
+
+    #[test]
+    fn overwriting_keys_does_not_grow_map() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+
+        let mut map = HashMap::new_in(&allocator);
+        let mut i = 0u32;
+        while map.is_empty() || map.len() < map.capacity() {
+            map.insert(i, i);
+            i += 1;
+        }
+
+        let capacity = map.capacity();
+        let before = allocator.stats();
+        assert_eq!(map.insert(0, 10), Some(0));
+        *map.entry(1).or_insert(0) += 10;
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(allocator.stats(), before);
+        assert_eq!((map[&0], map[&1]), (10, 11));
+    }
+
+    #[test]
+    fn growing_map_keeps_earlier_tables() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        allocator.alloc(0u8);
+        let before = allocator.stats().allocated_bytes;
+
+        let mut map = HashMap::with_capacity_in(100, &allocator);
+        let reserved = allocator.stats().allocated_bytes - before;
+        for i in 0..100u32 {
+            map.insert(i, i);
+        }
+
+        assert_eq!(allocator.stats().allocated_bytes - before, reserved);
+
+        // Old table is not the most recent allocation, so it is left behind
+        let text = allocator.alloc_str("after");
+        map.reserve(1000);
+        assert!(allocator.stats().allocated_bytes - before > reserved + text.len());
+        assert!((0..100).all(|i| map.get(&i) == Some(&i)));
+    }
+
+    #[test]
+    fn frozen_map_outlives_builder() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let shared = Rc::new(());
+
+        let frozen = {
+            let mut map = HashMap::new_in(&allocator);
+            for i in 0..50 {
+                map.insert(allocator.alloc_str(&i.to_string()) as &str, shared.clone());
+            }
+            super::freeze(map)
+        };
+
+        assert_eq!(frozen.len(), 50);
+        assert!(frozen.contains_key("49"));
+        assert_eq!(Rc::strong_count(&shared), 51);
+    }
+}
@@ -0,0 +1,366 @@
+//! Contains a hash set whose table is stored within an arena.
+//!
+//! # Example
+//!
+//! ```
+//! use bumpercar::prelude::*;
+//! use bumpercar::collections::{hash_set, HashSet};
+//!
+//! let mut arena = Arena::new();
+//! let allocator = arena.allocator();
+//!
+//! let mut keywords = HashSet::new_in(&allocator);
+//! keywords.extend(["fn", "let", "match"]);
+//!
+//! let keywords = hash_set::freeze(keywords);
+//! assert!(keywords.contains("let"));
+//! ```
+
+use super::hash_map::{LastTable, OldTables};
+use crate::{Allocator, Bump};
+use core::hash::{BuildHasher, Hash};
+use hashbrown::raw::RawTable;
+
+#[doc(no_inline)]
+pub use hashbrown::hash_map::DefaultHashBuilder;
+
+/// A hash set whose table is allocated with a [`Bump`] allocator.
+///
+/// This wraps a [`hashbrown::HashSet`] that allocates with a reference to the [`Bump`]
+/// allocator, and dereferences to it. The table is grown in the same way as a
+/// [`HashMap`](crate::collections::HashMap)'s, see its documentation for more information.
+pub struct HashSet<'a, T, S = DefaultHashBuilder, B = Allocator<'a>>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    set: hashbrown::HashSet<T, S, &'a B>,
+    old_tables: OldTables,
+}
+
+impl<'a, T, B> HashSet<'a, T, DefaultHashBuilder, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    /// Creates an empty [`HashSet`], which does not allocate until items are inserted.
+    pub fn new_in(bump: &'a B) -> Self {
+        Self::with_hasher_in(DefaultHashBuilder::default(), bump)
+    }
+
+    /// Creates an empty [`HashSet`] with space for at least `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table could not be allocated.
+    pub fn with_capacity_in(capacity: usize, bump: &'a B) -> Self {
+        Self::with_capacity_and_hasher_in(capacity, DefaultHashBuilder::default(), bump)
+    }
+}
+
+impl<'a, T, S, B> HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    /// Creates an empty [`HashSet`] which uses the given `hasher` to hash items.
+    pub fn with_hasher_in(hasher: S, bump: &'a B) -> Self {
+        Self {
+            set: hashbrown::HashSet::with_hasher_in(hasher, bump),
+            old_tables: OldTables::default(),
+        }
+    }
+
+    /// Creates an empty [`HashSet`] with space for at least `capacity` items, which uses the
+    /// given `hasher` to hash items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table could not be allocated.
+    pub fn with_capacity_and_hasher_in(capacity: usize, hasher: S, bump: &'a B) -> Self {
+        Self {
+            set: hashbrown::HashSet::with_capacity_and_hasher_in(capacity, hasher, bump),
+            old_tables: OldTables::default(),
+        }
+    }
+
+    /// Returns the allocator used to allocate the [`HashSet`]'s table.
+    #[inline(always)]
+    pub fn bump(&self) -> &'a B {
+        self.set.allocator()
+    }
+
+    /// Consumes the [`HashSet`], returning the underlying [`hashbrown::HashSet`].
+    #[inline(always)]
+    pub fn into_inner(self) -> hashbrown::HashSet<T, S, &'a B> {
+        let set = core::mem::ManuallyDrop::new(self);
+
+        // Safety: the set is not used again, since its destructor is not run
+        unsafe { core::ptr::read(&set.set) }
+    }
+}
+
+impl<'a, T, S, B> HashSet<'a, T, S, B>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    /// Reserves space for at least `additional` more items.
+    ///
+    /// If the table is the most recent allocation, the memory of the old table is reclaimed when
+    /// the set is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows, or if the table could not be allocated.
+    pub fn reserve(&mut self, additional: usize) {
+        if additional <= self.set.capacity() - self.set.len() {
+            return;
+        }
+
+        let bump = self.bump();
+        let last = LastTable::new(bump, self.set.raw_table().allocation_info());
+        self.set.reserve(additional);
+        self.old_tables
+            .grown(last, self.set.raw_table().allocation_info());
+    }
+
+    /// Adds an item to the [`HashSet`], returning `false` if it was already present.
+    ///
+    /// See [`hashbrown::HashSet::insert`] for more information.
+    pub fn insert(&mut self, item: T) -> bool {
+        if self.is_full() {
+            // The hashbrown::HashSet grows a full table even if the item is already present
+            if self.set.contains(&item) {
+                return false;
+            }
+
+            self.reserve(1);
+        }
+
+        self.set.insert(item)
+    }
+
+    /// Adds an item to the [`HashSet`], replacing and returning an equal item if it was already
+    /// present.
+    ///
+    /// See [`hashbrown::HashSet::replace`] for more information.
+    pub fn replace(&mut self, item: T) -> Option<T> {
+        if self.is_full() && !self.set.contains(&item) {
+            self.reserve(1);
+        }
+
+        self.set.replace(item)
+    }
+
+    /// Returns `true` if inserting a new item would grow the table.
+    fn is_full(&self) -> bool {
+        self.set.len() == self.set.capacity()
+    }
+}
+
+impl<'a, T, S, B> Drop for HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    fn drop(&mut self) {
+        let bump = self.bump();
+
+        // Frees the current table first, so that the old tables become the most recent allocation
+        drop(core::mem::replace(
+            self.set.raw_table_mut(),
+            RawTable::new_in(bump),
+        ));
+
+        self.old_tables.reclaim(bump);
+    }
+}
+
+impl<'a, T, S, B> core::ops::Deref for HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Target = hashbrown::HashSet<T, S, &'a B>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.set
+    }
+}
+
+impl<'a, T, S, B> core::ops::DerefMut for HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.set
+    }
+}
+
+impl<'a, T, S, B> Extend<T> for HashSet<'a, T, S, B>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        let items = items.into_iter();
+
+        // Some items may already be present, see hashbrown::HashSet::extend
+        let additional = if self.is_empty() {
+            items.size_hint().0
+        } else {
+            (items.size_hint().0 + 1) / 2
+        };
+
+        self.reserve(additional);
+        for item in items {
+            self.insert(item);
+        }
+    }
+}
+
+impl<'a, 's, T, S, B> IntoIterator for &'s HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Item = &'s T;
+    type IntoIter = hashbrown::hash_set::Iter<'s, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.iter()
+    }
+}
+
+impl<'a, T, S, B> IntoIterator for HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    type Item = T;
+    type IntoIter = hashbrown::hash_set::IntoIter<T, &'a B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_inner().into_iter()
+    }
+}
+
+impl<'a, T, S, B> core::fmt::Debug for HashSet<'a, T, S, B>
+where
+    T: core::fmt::Debug,
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.set, f)
+    }
+}
+
+/// Moves the `set` into the arena, returning a read-only reference to it that lives for the
+/// lifetime `'a` of the arena's allocations.
+///
+/// Note that the destructors for the set's items are **never run**.
+pub fn freeze<'a, T, S, B>(set: HashSet<'a, T, S, B>) -> &'a HashSet<'a, T, S, B>
+where
+    B: Bump<'a, 'a>,
+    &'a B: allocator_api2::alloc::Allocator,
+{
+    let bump = set.bump();
+    bump.alloc(set)
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::HashSet;
+    use crate::prelude::*;
+    use alloc::{rc::Rc, string::ToString};
+
+    #[test]
+    fn dropping_set_reclaims_table() {
+        let mut arena = Arena::with_capacity(1 << 16);
+        let allocator = arena.allocator();
+        allocator.alloc(0u8);
+        let before = allocator.stats().allocated_bytes;
+
+        // Starts out large enough that no padding is needed between tables
+        let mut set = HashSet::with_capacity_in(16, &allocator);
+        for i in 0..1000u32 {
+            set.insert(i);
+        }
+
+        assert!((0..1000).all(|i| set.contains(&i)));
+        assert!(allocator.stats().allocated_bytes > before);
+
+        // Old tables lie directly above the current table, so nothing is left behind
+        drop(set);
+        assert_eq!(allocator.stats().allocated_bytes, before);
+    }
+
+    #[test]
+    fn replacing_items_does_not_grow_set() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+
+        let mut set = HashSet::new_in(&allocator);
+        let mut i = 0u32;
+        while set.is_empty() || set.len() < set.capacity() {
+            set.insert(i);
+            i += 1;
+        }
+
+        let capacity = set.capacity();
+        let before = allocator.stats();
+        assert!(!set.insert(0));
+        assert_eq!(set.replace(1), Some(1));
+        assert_eq!(set.capacity(), capacity);
+        assert_eq!(allocator.stats(), before);
+    }
+
+    #[test]
+    fn frozen_set_outlives_builder() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+
+        let frozen = {
+            let mut set = HashSet::new_in(&allocator);
+            for i in 0..50 {
+                set.insert(allocator.alloc_str(&i.to_string()) as &str);
+            }
+            super::freeze(set)
+        };
+
+        assert_eq!(frozen.len(), 50);
+        assert!(frozen.contains("49"));
+        assert!(!frozen.contains("50"));
+    }
+
+    #[test]
+    fn items_that_need_drop() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let shared = Rc::new(());
+
+        let mut set = HashSet::new_in(&allocator);
+        for i in 0..100 {
+            set.insert((i, shared.clone()));
+        }
+
+        // Replaced items are dropped, and growing does not drop or duplicate any items
+        set.replace((7, shared.clone()));
+        assert_eq!(Rc::strong_count(&shared), 101);
+        set.retain(|(i, _)| i % 2 == 0);
+        assert_eq!(Rc::strong_count(&shared), 51);
+
+        let frozen = super::freeze(set);
+        assert_eq!(frozen.len(), 50);
+        assert_eq!(Rc::strong_count(&shared), 51);
+    }
+}
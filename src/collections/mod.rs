@@ -0,0 +1,22 @@
+//! Provides collection types whose contents are stored within an [`Arena`](crate::Arena).
+//!
+//! Collections refer to the [`Bump`](crate::Bump) allocator that they were created with, and
+//! allocate all of their memory with it.
+
+#[cfg(feature = "hashbrown")]
+pub mod hash_map;
+#[cfg(feature = "hashbrown")]
+pub mod hash_set;
+pub mod string;
+pub mod vec;
+
+#[cfg(feature = "hashbrown")]
+#[doc(no_inline)]
+pub use hash_map::HashMap;
+#[cfg(feature = "hashbrown")]
+#[doc(no_inline)]
+pub use hash_set::HashSet;
+#[doc(no_inline)]
+pub use string::String;
+#[doc(no_inline)]
+pub use vec::Vec;
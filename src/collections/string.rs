@@ -0,0 +1,426 @@
+//! Contains a growable UTF-8 string type whose buffer is stored within an arena.
+//!
+//! # Example
+//!
+//! ```
+//! use bumpercar::prelude::*;
+//! use bumpercar::collections::String;
+//! use core::fmt::Write;
+//!
+//! let mut arena = Arena::new();
+//! let allocator = arena.allocator();
+//!
+//! let mut greeting = String::from_str_in("Hello", &allocator);
+//! greeting.push_str(", ");
+//! write!(greeting, "{}!", "world").unwrap();
+//! assert_eq!(greeting, "Hello, world!");
+//!
+//! let greeting: &mut str = greeting.into_bump_str();
+//! assert_eq!(greeting, "Hello, world!");
+//! ```
+
+use crate::collections::Vec;
+use crate::{AllocError, Allocator, Bump};
+
+/// A growable UTF-8 string, whose buffer is allocated with a [`Bump`] allocator.
+///
+/// Like a [`Vec`], the buffer is grown in place if it is the most recent allocation. Text can be
+/// written to the string with its [`core::fmt::Write`] implementation, or with the
+/// [`format!`](crate::format!) macro.
+///
+/// See the [module level documentation](crate::collections::string) for more information.
+pub struct String<'a, B: Bump<'a, 'a> + ?Sized = Allocator<'a>> {
+    bytes: Vec<'a, u8, B>,
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> String<'a, B> {
+    /// Creates an empty [`String`], which does not allocate until text is added to it.
+    pub fn new_in(bump: &'a B) -> Self {
+        Self {
+            bytes: Vec::new_in(bump),
+        }
+    }
+
+    /// Creates an empty [`String`] with space for at least `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be allocated.
+    pub fn with_capacity_in(capacity: usize, bump: &'a B) -> Self {
+        Self {
+            bytes: Vec::with_capacity_in(capacity, bump),
+        }
+    }
+
+    /// Creates a [`String`] containing a copy of the given `text`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be allocated.
+    pub fn from_str_in(text: &str, bump: &'a B) -> Self {
+        let mut string = Self::with_capacity_in(text.len(), bump);
+        string.push_str(text);
+        string
+    }
+
+    /// Returns the allocator used to allocate the [`String`]'s buffer.
+    #[inline(always)]
+    pub fn bump(&self) -> &'a B {
+        self.bytes.bump()
+    }
+
+    /// Returns the length of the [`String`], in bytes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the [`String`] is empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the number of bytes that the [`String`] can contain before its buffer is grown.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Returns a string slice containing the [`String`]'s text.
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        // Safety: bytes are always valid UTF-8
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Returns a mutable string slice containing the [`String`]'s text.
+    #[inline(always)]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // Safety: bytes are always valid UTF-8
+        unsafe { core::str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Returns the bytes of the [`String`]'s text.
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Attempts to reserve space for at least `additional` more bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the buffer could not be grown, in which case the [`String`]
+    /// is left unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        self.bytes.try_reserve(additional)
+    }
+
+    /// Reserves space for at least `additional` more bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn reserve(&mut self, additional: usize) {
+        self.bytes.reserve(additional)
+    }
+
+    /// Shrinks the buffer to fit the [`String`]'s text.
+    ///
+    /// The unused space is only reclaimed if the buffer is the most recent allocation.
+    pub fn shrink_to_fit(&mut self) {
+        self.bytes.shrink_to_fit()
+    }
+
+    /// Appends the given `text` to the end of the [`String`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn push_str(&mut self, text: &str) {
+        self.bytes.extend_from_slice_copy(text.as_bytes())
+    }
+
+    /// Appends a character to the end of the [`String`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Removes the last character from the [`String`], returning [`None`] if it is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.bytes.truncate(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    /// Inserts a character at the given byte `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not on a [`char`] boundary, or if the buffer could not be grown.
+    pub fn insert(&mut self, index: usize, c: char) {
+        self.insert_str(index, c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Inserts the given `text` at the given byte `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not on a [`char`] boundary, or if the buffer could not be grown.
+    pub fn insert_str(&mut self, index: usize, text: &str) {
+        assert!(
+            self.as_str().is_char_boundary(index),
+            "insertion index {index} should be on a char boundary"
+        );
+
+        self.push_str(text);
+        self.bytes[index..].rotate_right(text.len());
+    }
+
+    /// Shortens the [`String`] to the given length in bytes.
+    ///
+    /// If `length` is not smaller than the current length, this does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` is not on a [`char`] boundary.
+    pub fn truncate(&mut self, length: usize) {
+        if length < self.len() {
+            assert!(
+                self.as_str().is_char_boundary(length),
+                "new length {length} should be on a char boundary"
+            );
+
+            self.bytes.truncate(length);
+        }
+    }
+
+    /// Removes all text from the [`String`], keeping its buffer.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+
+    /// Consumes the [`String`], returning a string slice containing its text that lives for the
+    /// lifetime `'a` of the arena's allocations.
+    ///
+    /// Unused space in the buffer is reclaimed if it is the most recent allocation.
+    pub fn into_bump_str(self) -> &'a mut str {
+        // Safety: bytes are always valid UTF-8
+        unsafe { core::str::from_utf8_unchecked_mut(self.bytes.into_bump_slice()) }
+    }
+
+    /// Consumes the [`String`], returning its bytes.
+    pub fn into_bytes(self) -> Vec<'a, u8, B> {
+        self.bytes
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::ops::Deref for String<'a, B> {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::ops::DerefMut for String<'a, B> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::convert::AsRef<str> for String<'a, B> {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::convert::AsRef<[u8]> for String<'a, B> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::borrow::Borrow<str> for String<'a, B> {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> Clone for String<'a, B> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+        }
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::fmt::Write for String<'a, B> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> core::fmt::Result {
+        self.bytes
+            .try_reserve(text.len())
+            .map_err(|_| core::fmt::Error)?;
+        self.push_str(text);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> core::fmt::Result {
+        self.write_str(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::fmt::Debug for String<'a, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::fmt::Display for String<'a, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> Extend<char> for String<'a, B> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, chars: I) {
+        let chars = chars.into_iter();
+        self.reserve(chars.size_hint().0);
+        for c in chars {
+            self.push(c);
+        }
+    }
+}
+
+impl<'a, 's, B: Bump<'a, 'a> + ?Sized> Extend<&'s str> for String<'a, B> {
+    fn extend<I: IntoIterator<Item = &'s str>>(&mut self, strings: I) {
+        for text in strings {
+            self.push_str(text);
+        }
+    }
+}
+
+impl<'a, B, C> PartialEq<String<'a, C>> for String<'a, B>
+where
+    B: Bump<'a, 'a> + ?Sized,
+    C: Bump<'a, 'a> + ?Sized,
+{
+    fn eq(&self, other: &String<'a, C>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> PartialEq<str> for String<'a, B> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> PartialEq<&str> for String<'a, B> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> Eq for String<'a, B> {}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> PartialOrd for String<'a, B> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> Ord for String<'a, B> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::hash::Hash for String<'a, B> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+/// Formats text directly into an arena, returning a `&'a mut str`.
+///
+/// The first argument is a reference to a [`Bump`](crate::Bump) allocator, followed by the
+/// format string and its arguments, as in [`core::format_args!`]. The text is written into a
+/// [`String`](crate::collections::String) that grows in place, so no temporary buffer is
+/// allocated on the heap.
+///
+/// # Panics
+///
+/// Panics if a formatting trait implementation returns an error, or if memory for the text could
+/// not be allocated.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::prelude::*;
+///
+/// let mut arena = Arena::new();
+/// let allocator = arena.allocator();
+/// let name = "world";
+/// let greeting = bumpercar::format!(in &allocator, "Hello, {name}! {}", 1 + 2);
+/// assert_eq!(greeting, "Hello, world! 3");
+/// ```
+#[macro_export]
+macro_rules! format {
+    (in $bump:expr, $($arg:tt)*) => {{
+        let mut string = $crate::collections::String::new_in($bump);
+        ::core::fmt::Write::write_fmt(&mut string, ::core::format_args!($($arg)*))
+            .expect("a formatting trait implementation returned an error");
+        string.into_bump_str()
+    }};
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::String;
+    use crate::prelude::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn editing_preserves_utf8() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut text = String::new_in(&allocator);
+        text.extend(['a', 'é', 'b']);
+        text.insert(1, '✓');
+        text.insert_str(0, "→ ");
+        assert_eq!(text, "→ a✓éb");
+        assert_eq!(text.pop(), Some('b'));
+        text.truncate("→ a".len());
+        assert_eq!(text, "→ a");
+    }
+
+    #[test]
+    fn format_grows_in_place() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        allocator.alloc(0u8);
+        let before = allocator.stats().allocated_bytes;
+
+        let items = (0..100).map(|i| i.to_string());
+        let mut joined = String::new_in(&allocator);
+        for item in items {
+            core::fmt::Write::write_fmt(&mut joined, format_args!("{item},")).unwrap();
+        }
+
+        let joined = joined.into_bump_str();
+        assert_eq!(allocator.stats().allocated_bytes - before, joined.len());
+
+        let formatted = crate::format!(in &allocator, "{}|{:>5}", &joined[..4], 42);
+        assert_eq!(formatted, "0,1,|   42");
+    }
+}
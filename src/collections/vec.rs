@@ -0,0 +1,1032 @@
+//! Contains a growable array type whose buffer is stored within an arena.
+//!
+//! # Example
+//!
+//! ```
+//! use bumpercar::prelude::*;
+//! use bumpercar::collections::Vec;
+//!
+//! let mut arena = Arena::new();
+//! let allocator = arena.allocator();
+//!
+//! let mut numbers = Vec::new_in(&allocator);
+//! numbers.extend([5, 3, 8, 1]);
+//! numbers.push(4);
+//! numbers.sort();
+//! numbers.retain(|n| n % 2 == 0);
+//! assert_eq!(numbers, [4, 8]);
+//!
+//! let numbers: &mut [i32] = numbers.into_bump_slice();
+//! assert_eq!(numbers, &[4, 8]);
+//! ```
+
+use crate::{AllocError, Allocator, Bump};
+use core::alloc::Layout;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::{Bound, RangeBounds};
+use core::ptr::NonNull;
+
+/// The capacity of a [`Vec`] after its first allocation.
+const MIN_CAPACITY: usize = 4;
+
+#[inline(always)]
+fn array_layout<T>(length: usize) -> Result<Layout, AllocError> {
+    Layout::array::<T>(length).map_err(|_| AllocError::LayoutTooLarge)
+}
+
+/// A contiguous growable array, whose buffer is allocated with a [`Bump`] allocator.
+///
+/// When more space is needed, the buffer is grown in place if it is the most recent allocation,
+/// see [`Bump::realloc_last`] for more information. Otherwise, the contents are copied into a new
+/// buffer, and the memory for the old buffer is only reclaimed once the arena is reset.
+///
+/// The [`Vec`] drops its items when it is dropped. To keep the items for the lifetime `'a` of
+/// the arena's allocations, see [`into_bump_slice`](Vec::into_bump_slice).
+///
+/// See the [module level documentation](crate::collections::vec) for more information.
+pub struct Vec<'a, T, B: Bump<'a, 'a> + ?Sized = Allocator<'a>> {
+    pointer: NonNull<T>,
+    capacity: usize,
+    length: usize,
+    bump: &'a B,
+    _items: PhantomData<T>,
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> Vec<'a, T, B> {
+    /// Creates an empty [`Vec`], which does not allocate until items are added to it.
+    pub fn new_in(bump: &'a B) -> Self {
+        Self {
+            pointer: NonNull::dangling(),
+            capacity: if core::mem::size_of::<T>() == 0 {
+                usize::MAX
+            } else {
+                0
+            },
+            length: 0,
+            bump,
+            _items: PhantomData,
+        }
+    }
+
+    /// Creates an empty [`Vec`] with space for at least `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be allocated.
+    pub fn with_capacity_in(capacity: usize, bump: &'a B) -> Self {
+        let mut vec = Self::new_in(bump);
+        vec.reserve(capacity);
+        vec
+    }
+
+    /// Creates a [`Vec`] containing the items yielded by an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be allocated.
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(items: I, bump: &'a B) -> Self {
+        let mut vec = Self::new_in(bump);
+        vec.extend(items);
+        vec
+    }
+
+    /// Returns the allocator used to allocate the [`Vec`]'s buffer.
+    #[inline(always)]
+    pub fn bump(&self) -> &'a B {
+        self.bump
+    }
+
+    /// Returns the number of items in the [`Vec`].
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the [`Vec`] contains no items.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of items that the [`Vec`] can contain before its buffer is grown.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns a slice containing the items in the [`Vec`].
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        // Safety: first length items are initialized
+        unsafe { core::slice::from_raw_parts(self.pointer.as_ptr(), self.length) }
+    }
+
+    /// Returns a mutable slice containing the items in the [`Vec`].
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // Safety: first length items are initialized
+        unsafe { core::slice::from_raw_parts_mut(self.pointer.as_ptr(), self.length) }
+    }
+
+    fn grow_to(&mut self, capacity: usize) -> Result<(), AllocError> {
+        let new_layout = array_layout::<T>(capacity)?;
+        let pointer = if self.capacity == 0 {
+            self.bump.try_alloc_with_layout(new_layout)?
+        } else {
+            // Layout is valid, since the buffer was already allocated
+            let old_layout = array_layout::<T>(self.capacity).unwrap();
+
+            // Safety: buffer is valid for the old layout, and is no longer used once it is moved
+            unsafe {
+                self.bump
+                    .try_realloc_with_layout(self.pointer.cast(), old_layout, new_layout)?
+            }
+        };
+
+        self.pointer = pointer.cast();
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    /// Attempts to reserve space for at least `additional` more items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if the buffer could not be grown, in which case the [`Vec`] is
+    /// left unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), AllocError> {
+        let required = self
+            .length
+            .checked_add(additional)
+            .ok_or(AllocError::LayoutTooLarge)?;
+
+        if required <= self.capacity {
+            return Ok(());
+        }
+
+        self.grow_to(
+            required
+                .max(self.capacity.saturating_mul(2))
+                .max(MIN_CAPACITY),
+        )
+    }
+
+    /// Reserves space for at least `additional` more items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).unwrap()
+    }
+
+    /// Shrinks the buffer to fit the items in the [`Vec`].
+    ///
+    /// The unused space is only reclaimed if the buffer is the most recent allocation.
+    pub fn shrink_to_fit(&mut self) {
+        if core::mem::size_of::<T>() == 0 || self.capacity == self.length {
+            return;
+        }
+
+        // Layouts are valid, since the buffer was already allocated
+        let old_layout = array_layout::<T>(self.capacity).unwrap();
+        let new_layout = array_layout::<T>(self.length).unwrap();
+
+        // Safety: buffer is valid for the old layout, and is no longer used once it is moved
+        if let Some(pointer) = unsafe {
+            self.bump
+                .realloc_last(self.pointer.cast(), old_layout, new_layout)
+        } {
+            self.pointer = pointer.cast();
+            self.capacity = self.length;
+        }
+    }
+
+    /// Appends an item to the end of the [`Vec`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn push(&mut self, item: T) {
+        if self.length == self.capacity {
+            self.reserve(1);
+        }
+
+        // Safety: length is less than capacity
+        unsafe { self.pointer.as_ptr().add(self.length).write(item) }
+        self.length += 1;
+    }
+
+    /// Removes the last item from the [`Vec`], returning [`None`] if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+
+        self.length -= 1;
+        // Safety: item is initialized, and is no longer accessible since length was decremented
+        Some(unsafe { self.pointer.as_ptr().add(self.length).read() })
+    }
+
+    /// Inserts an item at the given `index`, shifting all items after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`, or if the buffer could not be grown.
+    pub fn insert(&mut self, index: usize, item: T) {
+        assert!(
+            index <= self.length,
+            "insertion index {index} should be <= length {}",
+            self.length
+        );
+
+        if self.length == self.capacity {
+            self.reserve(1);
+        }
+
+        // Safety: index is in bounds, and the buffer has space for one more item
+        unsafe {
+            let destination = self.pointer.as_ptr().add(index);
+            core::ptr::copy(destination, destination.add(1), self.length - index);
+            destination.write(item);
+        }
+
+        self.length += 1;
+    }
+
+    /// Removes and returns the item at the given `index`, shifting all items after it to the
+    /// left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(
+            index < self.length,
+            "removal index {index} should be < length {}",
+            self.length
+        );
+
+        // Safety: index is in bounds, and the removed item is no longer accessible
+        unsafe {
+            let source = self.pointer.as_ptr().add(index);
+            let item = source.read();
+            core::ptr::copy(source.add(1), source, self.length - index - 1);
+            self.length -= 1;
+            item
+        }
+    }
+
+    /// Removes and returns the item at the given `index`, replacing it with the last item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(
+            index < self.length,
+            "swap_remove index {index} should be < length {}",
+            self.length
+        );
+
+        let last = self.length - 1;
+        self.as_mut_slice().swap(index, last);
+        self.pop().unwrap()
+    }
+
+    /// Shortens the [`Vec`] to the given `length`, dropping the removed items.
+    ///
+    /// If `length` is not smaller than the current length, this does nothing.
+    pub fn truncate(&mut self, length: usize) {
+        if length >= self.length {
+            return;
+        }
+
+        let removed = self.length - length;
+        self.length = length;
+
+        // Safety: removed items are initialized, and are no longer accessible
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                self.pointer.as_ptr().add(length),
+                removed,
+            ));
+        }
+    }
+
+    /// Removes all items from the [`Vec`], keeping its buffer.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Keeps only the items for which the closure returns `true`, preserving their order.
+    ///
+    /// If the closure panics, then the items that were not yet visited are kept.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let length = self.length;
+
+        // Items are only accounted for by the guard while the closure is running
+        self.length = 0;
+
+        let mut retain = Retain {
+            vec: self,
+            length,
+            processed: 0,
+            deleted: 0,
+        };
+
+        let items = retain.vec.pointer.as_ptr();
+        while retain.processed < length {
+            // Safety: index is in bounds, and each item is either moved or dropped exactly once
+            unsafe {
+                let item = items.add(retain.processed);
+                if f(&*item) {
+                    if retain.deleted > 0 {
+                        let destination = items.add(retain.processed - retain.deleted);
+                        core::ptr::copy_nonoverlapping(item, destination, 1);
+                    }
+                    retain.processed += 1;
+                } else {
+                    // Counted first, so the item is not moved if its destructor panics
+                    retain.processed += 1;
+                    retain.deleted += 1;
+                    core::ptr::drop_in_place(item);
+                }
+            }
+        }
+    }
+
+    /// Removes the items in the given `range` from the [`Vec`], returning an iterator over the
+    /// removed items.
+    ///
+    /// Any removed items that were not yielded are dropped once the iterator is dropped. If the
+    /// iterator is leaked, then the items after the range are also leaked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if the end is greater than
+    /// the length of the [`Vec`].
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, 'a, T, B> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range start overflowed"),
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range end overflowed"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.length,
+        };
+
+        assert!(
+            start <= end,
+            "drain range start {start} should be <= end {end}"
+        );
+        assert!(
+            end <= self.length,
+            "drain range end {end} should be <= length {}",
+            self.length
+        );
+
+        let tail_length = self.length - end;
+
+        // Drained items and items after the range are inaccessible until the iterator is dropped
+        self.length = start;
+
+        Drain {
+            index: start,
+            end,
+            tail_start: end,
+            tail_length,
+            vec: self,
+        }
+    }
+
+    /// Appends clones of the items in the `slice` to the end of the [`Vec`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn extend_from_slice(&mut self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(slice.len());
+        for item in slice {
+            self.push(item.clone());
+        }
+    }
+
+    /// Appends copies of the items in the `slice` to the end of the [`Vec`].
+    ///
+    /// This is faster than [`Vec::extend_from_slice`], since the items are copied all at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn extend_from_slice_copy(&mut self, slice: &[T])
+    where
+        T: Copy,
+    {
+        self.reserve(slice.len());
+
+        // Safety: capacity was reserved for the items, and the slice cannot overlap the spare
+        // capacity since it is borrowed separately
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                slice.as_ptr(),
+                self.pointer.as_ptr().add(self.length),
+                slice.len(),
+            );
+        }
+
+        self.length += slice.len();
+    }
+
+    /// Resizes the [`Vec`] to the given `length`, filling new items with clones of the `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer could not be grown.
+    pub fn resize(&mut self, length: usize, value: T)
+    where
+        T: Clone,
+    {
+        if length <= self.length {
+            self.truncate(length);
+            return;
+        }
+
+        self.reserve(length - self.length);
+        while self.length + 1 < length {
+            self.push(value.clone());
+        }
+
+        self.push(value);
+    }
+
+    /// Consumes the [`Vec`], returning a slice containing its items that lives for the lifetime
+    /// `'a` of the arena's allocations.
+    ///
+    /// Unused space in the buffer is reclaimed if it is the most recent allocation. Note that the
+    /// destructors for the items are **never run**, see
+    /// [`into_boxed_slice`](Vec::into_boxed_slice) for an alternative.
+    pub fn into_bump_slice(self) -> &'a mut [T] {
+        let mut vec = ManuallyDrop::new(self);
+        vec.shrink_to_fit();
+
+        // Safety: first length items are initialized, and buffer lives for 'a
+        unsafe { core::slice::from_raw_parts_mut(vec.pointer.as_ptr(), vec.length) }
+    }
+
+    /// Consumes the [`Vec`], returning a [`Box<'a, [T]>`](crate::boxed::Box) that drops the items
+    /// when it is dropped.
+    pub fn into_boxed_slice(self) -> crate::boxed::Box<'a, [T]> {
+        let bump = self.bump;
+        crate::boxed::Box::with_finger(self.into_bump_slice(), bump)
+    }
+}
+
+/// Moves the items that were not yet visited by [`Vec::retain`] after the kept items, and
+/// restores the length of the [`Vec`], even if the closure or a destructor panics.
+struct Retain<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> {
+    vec: &'v mut Vec<'a, T, B>,
+    length: usize,
+    processed: usize,
+    deleted: usize,
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> Drop for Retain<'_, 'a, T, B> {
+    fn drop(&mut self) {
+        let items = self.vec.pointer.as_ptr();
+        if self.deleted > 0 {
+            // Safety: unvisited items are initialized, and are moved to directly after the kept
+            // items
+            unsafe {
+                core::ptr::copy(
+                    items.add(self.processed),
+                    items.add(self.processed - self.deleted),
+                    self.length - self.processed,
+                );
+            }
+        }
+
+        self.vec.length = self.length - self.deleted;
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> Drop for Vec<'a, T, B> {
+    fn drop(&mut self) {
+        // Safety: items are initialized, and are not used after this point
+        unsafe { core::ptr::drop_in_place(self.as_mut_slice()) }
+
+        if core::mem::size_of::<T>() != 0 && self.capacity != 0 {
+            // Layout is valid, since the buffer was already allocated
+            let layout = array_layout::<T>(self.capacity).unwrap();
+
+            // Safety: buffer was allocated with the layout, and is not used after this point
+            unsafe {
+                self.bump.dealloc_last(self.pointer.cast(), layout);
+            }
+        }
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::ops::Deref for Vec<'a, T, B> {
+    type Target = [T];
+
+    #[inline(always)]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::ops::DerefMut for Vec<'a, T, B> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::convert::AsRef<[T]> for Vec<'a, T, B> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::convert::AsMut<[T]> for Vec<'a, T, B> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::borrow::Borrow<[T]> for Vec<'a, T, B> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::borrow::BorrowMut<[T]> for Vec<'a, T, B> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<'a, T: Clone, B: Bump<'a, 'a> + ?Sized> Clone for Vec<'a, T, B> {
+    fn clone(&self) -> Self {
+        let mut vec = Self::with_capacity_in(self.length, self.bump);
+        vec.extend_from_slice(self);
+        vec
+    }
+}
+
+impl<'a, T: core::fmt::Debug, B: Bump<'a, 'a> + ?Sized> core::fmt::Debug for Vec<'a, T, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> Extend<T> for Vec<'a, T, B> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        let items = items.into_iter();
+        self.reserve(items.size_hint().0);
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, 'i, T: Copy + 'i, B: Bump<'a, 'a> + ?Sized> Extend<&'i T> for Vec<'a, T, B> {
+    fn extend<I: IntoIterator<Item = &'i T>>(&mut self, items: I) {
+        self.extend(items.into_iter().copied())
+    }
+}
+
+impl<'a, T: PartialEq<U>, U, B, C> PartialEq<Vec<'a, U, C>> for Vec<'a, T, B>
+where
+    B: Bump<'a, 'a> + ?Sized,
+    C: Bump<'a, 'a> + ?Sized,
+{
+    fn eq(&self, other: &Vec<'a, U, C>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<'a, T: PartialEq<U>, U, B: Bump<'a, 'a> + ?Sized> PartialEq<[U]> for Vec<'a, T, B> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<'a, T: PartialEq<U>, U, B: Bump<'a, 'a> + ?Sized> PartialEq<&[U]> for Vec<'a, T, B> {
+    fn eq(&self, other: &&[U]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl<'a, T, U, B, const N: usize> PartialEq<[U; N]> for Vec<'a, T, B>
+where
+    T: PartialEq<U>,
+    B: Bump<'a, 'a> + ?Sized,
+{
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<'a, T: Eq, B: Bump<'a, 'a> + ?Sized> Eq for Vec<'a, T, B> {}
+
+impl<'a, T: PartialOrd, B: Bump<'a, 'a> + ?Sized> PartialOrd for Vec<'a, T, B> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<'a, T: Ord, B: Bump<'a, 'a> + ?Sized> Ord for Vec<'a, T, B> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<'a, T: core::hash::Hash, B: Bump<'a, 'a> + ?Sized> core::hash::Hash for Vec<'a, T, B> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> From<Vec<'a, T, B>> for crate::boxed::Box<'a, [T]> {
+    fn from(vec: Vec<'a, T, B>) -> Self {
+        vec.into_boxed_slice()
+    }
+}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> IntoIterator for &'v Vec<'a, T, B> {
+    type Item = &'v T;
+    type IntoIter = core::slice::Iter<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> IntoIterator for &'v mut Vec<'a, T, B> {
+    type Item = &'v mut T;
+    type IntoIter = core::slice::IterMut<'v, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> IntoIterator for Vec<'a, T, B> {
+    type Item = T;
+    type IntoIter = IntoIter<'a, T, B>;
+
+    fn into_iter(self) -> IntoIter<'a, T, B> {
+        let vec = ManuallyDrop::new(self);
+        IntoIter {
+            pointer: vec.pointer,
+            capacity: vec.capacity,
+            index: 0,
+            end: vec.length,
+            bump: vec.bump,
+            _items: PhantomData,
+        }
+    }
+}
+
+/// An iterator that moves the items out of a [`Vec`].
+///
+/// This is returned by the [`IntoIterator`] implementation for [`Vec`].
+pub struct IntoIter<'a, T, B: Bump<'a, 'a> + ?Sized = Allocator<'a>> {
+    pointer: NonNull<T>,
+    capacity: usize,
+    index: usize,
+    end: usize,
+    bump: &'a B,
+    _items: PhantomData<T>,
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> IntoIter<'a, T, B> {
+    /// Returns a slice containing the remaining items.
+    pub fn as_slice(&self) -> &[T] {
+        // Safety: items between index and end are initialized
+        unsafe {
+            core::slice::from_raw_parts(
+                self.pointer.as_ptr().add(self.index),
+                self.end - self.index,
+            )
+        }
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> Iterator for IntoIter<'a, T, B> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index == self.end {
+            return None;
+        }
+
+        self.index += 1;
+        // Safety: item is initialized, and is no longer accessible
+        Some(unsafe { self.pointer.as_ptr().add(self.index - 1).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> DoubleEndedIterator for IntoIter<'a, T, B> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.index == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        // Safety: item is initialized, and is no longer accessible
+        Some(unsafe { self.pointer.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> ExactSizeIterator for IntoIter<'a, T, B> {}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> core::iter::FusedIterator for IntoIter<'a, T, B> {}
+
+impl<'a, T, B: Bump<'a, 'a> + ?Sized> Drop for IntoIter<'a, T, B> {
+    fn drop(&mut self) {
+        // Safety: remaining items are initialized, and are not used after this point
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                self.pointer.as_ptr().add(self.index),
+                self.end - self.index,
+            ));
+        }
+
+        // Reclaims the buffer
+        drop(Vec {
+            pointer: self.pointer,
+            capacity: self.capacity,
+            length: 0,
+            bump: self.bump,
+            _items: PhantomData,
+        });
+    }
+}
+
+impl<'a, T: core::fmt::Debug, B: Bump<'a, 'a> + ?Sized> core::fmt::Debug for IntoIter<'a, T, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+/// An iterator that removes a range of items from a [`Vec`].
+///
+/// This is returned by [`Vec::drain`].
+pub struct Drain<'v, 'a, T, B: Bump<'a, 'a> + ?Sized = Allocator<'a>> {
+    vec: &'v mut Vec<'a, T, B>,
+    index: usize,
+    end: usize,
+    tail_start: usize,
+    tail_length: usize,
+}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> Drain<'v, 'a, T, B> {
+    /// Returns a slice containing the remaining items.
+    pub fn as_slice(&self) -> &[T] {
+        // Safety: items between index and end are initialized
+        unsafe {
+            core::slice::from_raw_parts(
+                self.vec.pointer.as_ptr().add(self.index),
+                self.end - self.index,
+            )
+        }
+    }
+}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> Iterator for Drain<'v, 'a, T, B> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index == self.end {
+            return None;
+        }
+
+        self.index += 1;
+        // Safety: item is initialized, and is no longer accessible
+        Some(unsafe { self.vec.pointer.as_ptr().add(self.index - 1).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> DoubleEndedIterator for Drain<'v, 'a, T, B> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.index == self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        // Safety: item is initialized, and is no longer accessible
+        Some(unsafe { self.vec.pointer.as_ptr().add(self.end).read() })
+    }
+}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> ExactSizeIterator for Drain<'v, 'a, T, B> {}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> core::iter::FusedIterator for Drain<'v, 'a, T, B> {}
+
+impl<'v, 'a, T, B: Bump<'a, 'a> + ?Sized> Drop for Drain<'v, 'a, T, B> {
+    fn drop(&mut self) {
+        let remaining = self.end - self.index;
+        let items = self.vec.pointer.as_ptr();
+
+        // Safety: remaining items are initialized, and are no longer accessible
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                items.add(self.index),
+                remaining,
+            ));
+        }
+
+        // Safety: tail is initialized, and is moved to directly after the kept items
+        unsafe {
+            core::ptr::copy(
+                items.add(self.tail_start),
+                items.add(self.vec.length),
+                self.tail_length,
+            );
+        }
+
+        self.vec.length += self.tail_length;
+    }
+}
+
+impl<'v, 'a, T: core::fmt::Debug, B> core::fmt::Debug for Drain<'v, 'a, T, B>
+where
+    B: Bump<'a, 'a> + ?Sized,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::Vec;
+    use crate::prelude::*;
+    use alloc::{rc::Rc, string::ToString};
+
+    #[test]
+    fn grows_in_place_when_most_recent() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        allocator.alloc(0u64);
+        let before = allocator.stats().allocated_bytes;
+
+        let mut numbers = Vec::new_in(&allocator);
+        for i in 0..100u64 {
+            numbers.push(i);
+        }
+
+        // Growing in place wastes no memory on old buffers
+        assert_eq!(
+            allocator.stats().allocated_bytes - before,
+            numbers.capacity() * 8
+        );
+
+        let numbers = numbers.into_bump_slice();
+        assert_eq!(allocator.stats().allocated_bytes - before, 100 * 8);
+        assert!(numbers.iter().copied().eq(0..100));
+    }
+
+    #[test]
+    fn copied_slices_are_appended() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut numbers = Vec::new_in(&allocator);
+        numbers.push(0u32);
+        numbers.extend_from_slice_copy(&[1, 2, 3]);
+        numbers.extend_from_slice_copy(&[]);
+        numbers.extend_from_slice(&[4, 5]);
+        assert_eq!(numbers[..], [0, 1, 2, 3, 4, 5]);
+
+        let mut units = Vec::new_in(&allocator);
+        units.extend_from_slice_copy(&[(); 10]);
+        assert_eq!(units.len(), 10);
+    }
+
+    #[test]
+    fn interleaved_allocations_copy_buffer() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut names = Vec::new_in(&allocator);
+        for i in 0..50 {
+            names.push(&*allocator.alloc_str(&i.to_string()));
+        }
+
+        names.sort();
+        assert_eq!(names[..3], ["0", "1", "10"]);
+        names.insert(0, "first");
+        assert_eq!(names.remove(1), "0");
+        assert_eq!(names.swap_remove(0), "first");
+        assert_eq!(names.len(), 49);
+    }
+
+    #[test]
+    fn items_are_dropped() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let shared = Rc::new(());
+
+        let mut items = Vec::from_iter_in((0..10).map(|_| shared.clone()), &allocator);
+        let mut index = 0;
+        items.retain(|_| {
+            index += 1;
+            index % 2 == 0
+        });
+        assert_eq!(Rc::strong_count(&shared), 6);
+
+        let drained = items.drain(1..3).count();
+        assert_eq!(drained, 2);
+        assert_eq!(items.len(), 3);
+        assert_eq!(Rc::strong_count(&shared), 4);
+
+        // Items not yielded by the iterator are dropped
+        let mut iter = items.clone().into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(Rc::strong_count(&shared), 4);
+
+        let boxed = crate::boxed::Box::from(items);
+        assert_eq!(boxed.len(), 3);
+        drop(boxed);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", panic = "unwind"))]
+    fn retain_keeps_unvisited_items_on_panic() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let shared = Rc::new(());
+        let mut items = Vec::from_iter_in((0..6).map(|i| (i, shared.clone())), &allocator);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            items.retain(|(i, _)| match i {
+                4 => panic!("predicate failed"),
+                i => i % 2 == 0,
+            })
+        }));
+
+        assert!(result.is_err());
+        assert!(items.iter().map(|(i, _)| *i).eq([0, 2, 4, 5]));
+        assert_eq!(Rc::strong_count(&shared), 5);
+
+        drop(items);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn drain_keeps_tail() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut numbers = Vec::from_iter_in(0..10, &allocator);
+
+        let mut drain = numbers.drain(2..=5);
+        assert_eq!(drain.next_back(), Some(5));
+        assert_eq!(drain.as_slice(), [2, 3, 4]);
+        drop(drain);
+        assert_eq!(numbers, [0, 1, 6, 7, 8, 9]);
+
+        numbers.drain(..).for_each(drop);
+        assert!(numbers.is_empty());
+
+        numbers.extend(&[1, 2]);
+        numbers.resize(4, 0);
+        assert_eq!(numbers, [1, 2, 0, 0]);
+        assert_eq!(numbers.pop(), Some(0));
+    }
+
+    #[test]
+    fn zero_sized_items_do_not_allocate() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let before = allocator.stats();
+        let mut units = Vec::new_in(&allocator);
+        for _ in 0..1000 {
+            units.push(());
+        }
+
+        assert_eq!(units.len(), 1000);
+        assert_eq!(allocator.stats(), before);
+    }
+}
@@ -0,0 +1,41 @@
+/// The error type returned when a fallible allocation request into an arena fails.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{AllocError, prelude::*};
+///
+/// let mut arena = Arena::new();
+/// let allocator = arena.allocator();
+/// assert_eq!(allocator.try_alloc(5u32).copied(), Ok(5));
+/// assert_eq!(
+///     allocator.try_alloc_slice_uninit::<u64>(usize::MAX).err(),
+///     Some(AllocError::LayoutTooLarge)
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum AllocError {
+    /// The underlying memory allocator failed to allocate a new chunk.
+    OutOfMemory,
+    /// The size of the requested allocation, or of the chunk needed to contain it, is too large
+    /// to be described by a [`Layout`](core::alloc::Layout).
+    LayoutTooLarge,
+    /// A new chunk could not be allocated without exceeding the arena's memory limit.
+    ///
+    /// See [`ArenaBuilder::memory_limit`](crate::ArenaBuilder::memory_limit) for more information.
+    LimitExceeded,
+}
+
+impl core::fmt::Display for AllocError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::OutOfMemory => "out of memory",
+            Self::LayoutTooLarge => "allocation layout is too large",
+            Self::LimitExceeded => "arena memory limit exceeded",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
@@ -1,4 +1,6 @@
 use crate::raw_arena::{RawArena, RawArenaState};
+use crate::{Checkpoint, ChunkAllocator, Global};
+use core::marker::PhantomData;
 
 /// A bump allocator that allocates objects into a portion of an [`Arena`](crate::Arena).
 /// # Examples
@@ -37,54 +39,243 @@ use crate::raw_arena::{RawArena, RawArenaState};
 /// });
 /// ```
 #[derive(Debug)]
-pub struct Frame<'a: 'f, 'f> {
-    arena: &'f mut &'a mut RawArena,
+pub struct Frame<'a: 'f, 'f, A: ChunkAllocator = Global> {
+    arena: &'f mut RawArena<A>,
+    _parent: PhantomData<&'f mut &'a ()>,
 }
 
-impl<'a: 'f, 'f> Frame<'a, 'f> {
-    pub(crate) fn in_arena<T, F: FnOnce(&mut Frame<'a, '_>) -> T>(
-        mut arena: &'a mut RawArena,
-        f: F,
-    ) -> T {
-        let state: Option<RawArenaState> = arena.current_state();
-        let mut frame = Frame::<'a, '_> { arena: &mut arena };
-
-        // If a panic occurs, then bump pointer does not get adjusted back
-        // Only problem is unused memory (memory leak), which is not unsafe or UB
-        let result = f(&mut frame);
-
-        // Safety: The calls are nested correctly. Note that unlike alloc_try_with_layout,
-        // &mut access to arena ensures closure cannot issue direct calls to the arena's
-        // allocation functions, so all subsequent allocations are tied to the lifetime of the
-        // Frame.
-        unsafe {
-            arena.restore_state(state);
+/// Rolls back the allocations of a frame passed to
+/// [`Bump::try_with_frame`](crate::Bump::try_with_frame), unless it succeeds.
+struct Transaction<'a, 'f, A: ChunkAllocator> {
+    frame: Frame<'a, 'f, A>,
+    state: Option<RawArenaState>,
+    succeeded: bool,
+}
+
+impl<A: ChunkAllocator> Drop for Transaction<'_, '_, A> {
+    fn drop(&mut self) {
+        if !self.succeeded {
+            // Safety: references to the frame's allocations are tied to the lifetime 'f, which
+            // mutably borrows the parent allocator. It is only handed back on success, so the
+            // memory is not reused while they remain. Nested frames borrow this frame mutably, so
+            // they have already ended.
+            unsafe { self.frame.arena.roll_back(self.state) }
         }
+    }
+}
+
+impl<'f, A: ChunkAllocator> Frame<'f, 'f, A> {
+    pub(crate) fn try_in_arena<T, E, F>(arena: &'f mut RawArena<A>, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Frame<'_, 'f, A>) -> Result<T, E>,
+    {
+        // The transaction also rolls back if the closure panics. The closure cannot swap the
+        // frame with one from another transaction, since its first lifetime is unique.
+        let mut transaction = Transaction {
+            state: arena.current_state(),
+            frame: Frame {
+                arena,
+                _parent: PhantomData,
+            },
+            succeeded: false,
+        };
 
+        let result = f(&mut transaction.frame);
+        transaction.succeeded = result.is_ok();
         result
     }
 }
 
+impl<'a: 'f, 'f, A: ChunkAllocator> Frame<'a, 'f, A> {
+    pub(crate) fn in_arena<T, F: FnOnce(&mut Frame<'a, '_, A>) -> T>(
+        arena: &'a mut RawArena<A>,
+        f: F,
+    ) -> T {
+        // The checkpoint restores the arena's state once the closure returns, or if it panics.
+        // Note that unlike alloc_try_with_layout, &mut access to arena ensures closure cannot
+        // issue direct calls to the arena's allocation functions, so all subsequent allocations
+        // are tied to the lifetime of the Frame, and cannot escape in a panic payload.
+        let mut checkpoint = Checkpoint::in_arena(arena);
+        let mut frame = Frame::<'a, '_, A> {
+            arena: checkpoint.arena_mut(),
+            _parent: PhantomData,
+        };
+
+        f(&mut frame)
+    }
+
+    /// Returns a snapshot of the underlying arena's memory usage, including allocations made
+    /// outside of this frame.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Returns an iterator describing each of the arena's chunks, from the first chunk to the
+    /// last.
+    ///
+    /// See [`ChunkInfo`](crate::ChunkInfo) for more information.
+    pub fn chunks(&self) -> crate::ArenaChunks<'_> {
+        self.arena.chunk_infos()
+    }
+
+    /// Returns a [`Checkpoint`](crate::Checkpoint) that frees the objects allocated with it once
+    /// it is dropped or rolled back.
+    pub fn checkpoint(&mut self) -> crate::Checkpoint<'_, A> {
+        crate::Checkpoint::in_arena(self.arena)
+    }
+}
+
+impl<A: ChunkAllocator> crate::bump::private::Sealed for Frame<'_, '_, A> {
+    #[inline(always)]
+    fn current_finger(&self) -> Option<&core::sync::atomic::AtomicPtr<u8>> {
+        self.arena.current_finger()
+    }
+}
+
 // Safety: 'f is the lifetime of the frame, which is less than the lifetime of the arena 'a,
 // so allocations live for the lifetime of the frame
-unsafe impl<'a: 'f, 'f: 'me, 'me> crate::Bump<'me, 'f> for Frame<'a, 'f> {
-    #[inline(always)]
-    fn with_frame<T, F: FnOnce(&mut Frame) -> T>(&'me mut self, f: F) -> T {
-        Frame::in_arena::<T, F>(self.arena, f)
+unsafe impl<'a: 'f, 'f: 'me, 'me, A: ChunkAllocator> crate::Bump<'me, 'f> for Frame<'a, 'f, A> {
+    type ChunkAllocator = A;
+
+    crate::bump::forward_bump_methods!('me, 'f);
+}
+
+#[cfg(feature = "allocator-api2")]
+// Safety: allocations live for the lifetime of the frame, which outlives the reference
+crate::allocator_api::impl_allocator!([A: ChunkAllocator] Frame<'_, '_, A>);
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    #[cfg(all(feature = "std", panic = "unwind"))]
+    fn panic_in_frame_restores_state() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let first = allocator.alloc(1u32) as *const u32;
+        let before = allocator.stats();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            allocator.with_frame(|frame| {
+                frame.alloc_slice_fill(8192, 0u8);
+                frame.with_frame(|frame| {
+                    frame.alloc(2u64);
+                    panic!("oops");
+                });
+            })
+        }));
+
+        assert!(result.is_err());
+        let after = allocator.stats();
+        assert_eq!(after.allocated_bytes, before.allocated_bytes);
+        assert_eq!(
+            after.current_chunk_remaining,
+            before.current_chunk_remaining
+        );
+        assert_eq!(allocator.alloc(3u32) as *const u32, first.wrapping_sub(1));
     }
 
-    #[inline(always)]
-    fn alloc_with_layout(&'me self, layout: core::alloc::Layout) -> core::ptr::NonNull<u8> {
-        self.arena.alloc_with_layout(layout)
+    #[test]
+    #[cfg(all(feature = "std", panic = "unwind"))]
+    fn panic_in_first_frame_resets_arena() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            allocator.with_frame(|frame| {
+                frame.alloc(5u8);
+                panic!("oops");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(allocator.stats().allocated_bytes, 0);
     }
 
-    #[inline(always)]
-    unsafe fn alloc_try_with_layout<R, F>(&'me self, layout: core::alloc::Layout, f: F) -> R
-    where
-        R: crate::private::Try,
-        F: FnOnce(core::ptr::NonNull<u8>) -> R,
-    {
-        // Safety: ensured by caller
-        unsafe { self.arena.alloc_try_with_layout(layout, f) }
+    #[test]
+    #[cfg(all(feature = "std", panic = "unwind"))]
+    fn panic_in_try_frame_rolls_back() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        allocator.alloc(1u32);
+        let before = allocator.stats();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = allocator.try_with_frame(|frame| -> Result<(), ()> {
+                frame.alloc_slice_fill(8192, 0u8);
+                panic!("oops")
+            });
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(allocator.stats().allocated_bytes, before.allocated_bytes);
+    }
+
+    #[test]
+    fn parent_allocates_while_try_frame_result_is_held() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let (word, allocator) = allocator
+            .try_with_frame(|frame| Ok::<_, ()>(&*frame.alloc_str("kept")))
+            .unwrap();
+
+        let pair = allocator.alloc((word, 5u32));
+        let before = allocator.stats();
+        let failed = allocator.try_with_frame(|frame| Err::<(), _>(frame.alloc(*pair).1));
+        assert_eq!(failed.err(), Some(5));
+        assert_eq!(allocator.stats(), before);
+
+        let (words, _) = allocator
+            .try_with_frame(|frame| Ok::<_, ()>(&*frame.alloc_slice_cloned(&[word, "more"])))
+            .unwrap();
+        assert_eq!(words, ["kept", "more"]);
+        assert_eq!(*pair, ("kept", 5));
+    }
+
+    #[test]
+    fn try_with_frame_rolls_back_failures() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let kept = allocator.alloc(1u16);
+        let before = allocator.stats();
+
+        let failed = allocator.try_with_frame(|frame| {
+            frame.alloc_slice_fill(8192, 0u8);
+            let (inner, _) = frame.try_with_frame(|frame| Ok(*frame.alloc(2u8)))?;
+            if inner == 0 {
+                Ok(())
+            } else {
+                Err(())
+            }
+        });
+
+        assert!(failed.is_err());
+        assert_eq!(allocator.stats().allocated_bytes, before.allocated_bytes);
+
+        let (succeeded, allocator) = allocator
+            .try_with_frame(|frame| Ok::<_, ()>(frame.alloc_slice_fill(8192, 3u8)))
+            .unwrap();
+
+        assert_eq!(succeeded.len(), 8192);
+        assert_eq!(
+            allocator.stats().allocated_bytes,
+            before.allocated_bytes + 8192
+        );
+        assert_eq!(*kept, 1);
+    }
+
+    #[test]
+    fn try_with_frame_keeps_failures_that_need_dropping() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let before = allocator.stats();
+
+        let failed = allocator.try_with_frame(|frame| {
+            Err::<(), _>(frame.alloc_with_drop(alloc::vec::Vec::from([1, 2, 3])))
+        });
+
+        assert_eq!(failed.err().unwrap()[..], [1, 2, 3]);
+        assert!(allocator.stats().allocated_bytes > before.allocated_bytes);
     }
 }
@@ -0,0 +1,177 @@
+use crate::{Arena, ChunkAllocator};
+use core::num::NonZeroUsize;
+use core::ptr::NonNull;
+
+/// Refers to an object in an [`Arena`], and can be kept across calls to
+/// [`Arena::reset`].
+///
+/// A [`Handle`] is not tied to the lifetime of an [`Allocator`](crate::Allocator), so it can be
+/// cached for longer than the borrow of the arena used to allocate it. Once the arena is reset, its
+/// [`generation`](Arena::generation) changes, and the handle becomes stale, so
+/// [`get`](Handle::get) returns [`None`]. The object is dropped when the arena is reset or
+/// dropped.
+///
+/// Handles are created with [`Allocator::alloc_handle`](crate::Allocator::alloc_handle).
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::prelude::*;
+///
+/// let mut arena = Arena::new();
+/// let handle = arena.allocator().alloc_handle(String::from("cached"));
+/// assert_eq!(handle.get(&arena).map(String::as_str), Some("cached"));
+///
+/// handle.get_mut(&mut arena).unwrap().push('!');
+/// assert_eq!(handle.get(&arena).map(String::as_str), Some("cached!"));
+///
+/// arena.reset();
+/// assert_eq!(handle.get(&arena), None);
+/// ```
+pub struct Handle<T> {
+    pointer: NonNull<T>,
+    arena: NonZeroUsize,
+    generation: u64,
+}
+
+impl<T> Handle<T> {
+    pub(crate) fn new(pointer: NonNull<T>, arena: NonZeroUsize, generation: u64) -> Self {
+        Self {
+            pointer,
+            arena,
+            generation,
+        }
+    }
+
+    /// Returns the [`generation`](Arena::generation) of the arena when the handle was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns `true` if the handle refers to an object in the given `arena`, and the arena has
+    /// not been reset since the handle was created.
+    pub fn is_valid<A: ChunkAllocator>(&self, arena: &Arena<A>) -> bool {
+        let raw = arena.raw_arena();
+        raw.handle_id() == Some(self.arena) && raw.generation() == self.generation
+    }
+
+    /// Returns a reference to the object, or [`None`] if the handle is stale or was created by
+    /// another arena.
+    pub fn get<'r, A: ChunkAllocator>(&self, arena: &'r Arena<A>) -> Option<&'r T> {
+        if self.is_valid(arena) {
+            // Safety: object is still allocated, and no Allocator for the arena exists, so it
+            // cannot be mutated
+            Some(unsafe { self.pointer.as_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the object, or [`None`] if the handle is stale or was
+    /// created by another arena.
+    pub fn get_mut<'r, A: ChunkAllocator>(&self, arena: &'r mut Arena<A>) -> Option<&'r mut T> {
+        if self.is_valid(arena) {
+            let mut pointer = self.pointer;
+
+            // Safety: object is still allocated, and &mut borrow of the arena ensures exclusive
+            // access
+            Some(unsafe { pointer.as_mut() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pointer == other.pointer
+            && self.arena == other.arena
+            && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> core::hash::Hash for Handle<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.pointer.hash(state);
+        self.arena.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> core::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Handle")
+            .field("pointer", &self.pointer)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+// Safety: objects are only accessed through the arena, which must be sent along with the handle
+unsafe impl<T: Send> Send for Handle<T> {}
+
+// Safety: sharing a handle only allows copying it
+unsafe impl<T: Send> Sync for Handle<T> {}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn handles_from_other_arenas_are_rejected() {
+        let mut first = Arena::new();
+        let second = Arena::new();
+        let handle = first.allocator().alloc_handle(1u32);
+        assert_eq!(handle.get(&second), None);
+        assert_eq!(handle.get(&first), Some(&1));
+    }
+
+    #[test]
+    fn handle_values_are_dropped() {
+        let shared = alloc::sync::Arc::new(());
+        let mut arena = Arena::new();
+        let handle = arena.allocator().alloc_handle(shared.clone());
+        assert_eq!(alloc::sync::Arc::strong_count(&shared), 2);
+
+        arena.reset();
+        assert_eq!(handle.get(&arena), None);
+        assert_eq!(alloc::sync::Arc::strong_count(&shared), 1);
+
+        arena.allocator().alloc_handle(shared.clone());
+        drop(arena);
+        assert_eq!(alloc::sync::Arc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn handles_outlive_frames_but_not_resets() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let kept = allocator.alloc_handle(1u32);
+        allocator.with_frame(|_| ());
+        assert_eq!(arena.generation(), 0);
+        assert_eq!(kept.get(&arena), Some(&1));
+
+        let mut allocator = arena.allocator();
+        let result = allocator.try_with_frame(|frame| {
+            frame.alloc(2u32);
+            Err::<(), _>(())
+        });
+        assert!(result.is_err());
+        assert_eq!(arena.generation(), 0);
+        assert_eq!(kept.get(&arena), Some(&1));
+
+        arena.reset_and_shrink(crate::RetentionPolicy::Coalesce);
+        assert_eq!(arena.generation(), 1);
+        assert_eq!(kept.get(&arena), None);
+    }
+}
@@ -0,0 +1,561 @@
+//! Contains an arena whose objects are referred to by compact handles rather than references.
+//!
+//! See [`IndexArena`] for more information.
+
+use crate::raw_arena::RawArena;
+use crate::{AllocError, ChunkAllocator, Global};
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// The first block contains `1 << FIRST_BLOCK_SHIFT` items, and each block after it is twice as
+/// large as the previous one.
+const FIRST_BLOCK_SHIFT: u32 = 4;
+
+/// The number of blocks needed to contain an item for every possible [`Idx`].
+const BLOCK_COUNT: usize = (u32::BITS + 1 - FIRST_BLOCK_SHIFT) as usize;
+
+/// Returns the block containing the item at the given `index`, and the item's offset within it.
+#[inline(always)]
+fn locate(index: u32) -> (usize, usize) {
+    let biased = u64::from(index) + (1 << FIRST_BLOCK_SHIFT);
+    let shift = u64::BITS - 1 - biased.leading_zeros();
+    let block = (shift - FIRST_BLOCK_SHIFT) as usize;
+    (block, (biased - (1 << shift)) as usize)
+}
+
+/// Returns the number of items in the given `block`, saturating if it does not fit in a
+/// [`usize`].
+#[inline(always)]
+fn block_capacity(block: usize) -> usize {
+    1usize
+        .checked_shl(block as u32 + FIRST_BLOCK_SHIFT)
+        .unwrap_or(usize::MAX)
+}
+
+#[cfg(feature = "checked-idx")]
+fn next_arena_id() -> u32 {
+    static NEXT: core::sync::atomic::AtomicU32 = core::sync::atomic::AtomicU32::new(0);
+    NEXT.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
+}
+
+/// A handle to an object in an [`IndexArena`].
+///
+/// Unlike a reference, an [`Idx`] is not tied to the lifetime of the arena, so objects can refer
+/// to each other cyclically, and handles can be stored in hash maps. Handles are 4 bytes, unless
+/// the `checked-idx` feature is enabled.
+///
+/// When the `checked-idx` feature is enabled, each handle also contains an identifier for the
+/// arena that created it, so that using a handle with the wrong arena causes a panic, and handles
+/// from different arenas are never equal.
+pub struct Idx<T> {
+    index: u32,
+    #[cfg(feature = "checked-idx")]
+    arena: u32,
+    _type: PhantomData<fn() -> T>,
+}
+
+#[cfg(not(feature = "checked-idx"))]
+const _: () = assert!(core::mem::size_of::<Idx<u64>>() == 4);
+
+impl<T> Idx<T> {
+    /// Returns the index of the object, in the order that objects were allocated.
+    #[inline(always)]
+    pub const fn as_u32(self) -> u32 {
+        self.index
+    }
+}
+
+impl<T> Clone for Idx<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Idx<T> {}
+
+impl<T> PartialEq for Idx<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "checked-idx")]
+        if self.arena != other.arena {
+            return false;
+        }
+
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Idx<T> {}
+
+impl<T> PartialOrd for Idx<T> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Idx<T> {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let ordering = self.index.cmp(&other.index);
+
+        #[cfg(feature = "checked-idx")]
+        let ordering = ordering.then(self.arena.cmp(&other.arena));
+
+        ordering
+    }
+}
+
+impl<T> core::hash::Hash for Idx<T> {
+    #[inline(always)]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+
+        #[cfg(feature = "checked-idx")]
+        self.arena.hash(state);
+    }
+}
+
+impl<T> core::fmt::Debug for Idx<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Idx").field(&self.index).finish()
+    }
+}
+
+/// An arena whose objects are referred to by an [`Idx`] handle.
+///
+/// Objects are stored in blocks of increasing size within the arena's chunks, so looking up an
+/// object by its handle takes constant time, and objects are never moved. Objects are dropped
+/// when the arena is dropped.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{Idx, IndexArena};
+///
+/// struct Node {
+///     name: &'static str,
+///     next: Option<Idx<Node>>,
+/// }
+///
+/// let mut arena = IndexArena::new();
+/// let first = arena.alloc(Node { name: "first", next: None });
+/// let second = arena.alloc(Node { name: "second", next: Some(first) });
+/// arena[first].next = Some(second);
+///
+/// let next = arena[first].next.unwrap();
+/// assert_eq!(arena[next].name, "second");
+/// assert_eq!(arena[second].next, Some(first));
+/// ```
+pub struct IndexArena<T, A: ChunkAllocator = Global> {
+    arena: RawArena<A>,
+    blocks: [Cell<Option<NonNull<T>>>; BLOCK_COUNT],
+    length: Cell<usize>,
+    #[cfg(feature = "checked-idx")]
+    id: u32,
+    _items: PhantomData<T>,
+}
+
+impl<T> IndexArena<T> {
+    /// Creates an empty [`IndexArena`].
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: ChunkAllocator> IndexArena<T, A> {
+    /// Creates an empty [`IndexArena`] whose chunks are allocated with the given
+    /// [`ChunkAllocator`].
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            arena: RawArena::new_in(allocator),
+            blocks: Default::default(),
+            length: Cell::new(0),
+            #[cfg(feature = "checked-idx")]
+            id: next_arena_id(),
+            _items: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the [`ChunkAllocator`] used to allocate the arena's chunks.
+    pub fn chunk_allocator(&self) -> &A {
+        self.arena.chunk_allocator()
+    }
+
+    /// Returns the number of objects in the arena.
+    pub fn len(&self) -> usize {
+        self.length.get()
+    }
+
+    /// Returns `true` if the arena does not contain any objects.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a snapshot of the arena's memory usage.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    #[inline(always)]
+    fn handle(&self, index: u32) -> Idx<T> {
+        Idx {
+            index,
+            #[cfg(feature = "checked-idx")]
+            arena: self.id,
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns a pointer to the object with the given `index`, if it was allocated.
+    #[inline(always)]
+    fn item(&self, index: u32) -> Option<NonNull<T>> {
+        if index as usize >= self.len() {
+            return None;
+        }
+
+        let (block, offset) = locate(index);
+        self.blocks[block].get().map(|items| {
+            // Safety: offset is within the bounds of the block
+            unsafe { NonNull::new_unchecked(items.as_ptr().add(offset)) }
+        })
+    }
+
+    /// Returns a pointer to the object referred to by the `handle`.
+    #[inline(always)]
+    fn lookup(&self, handle: Idx<T>) -> Option<NonNull<T>> {
+        #[cfg(feature = "checked-idx")]
+        assert_eq!(handle.arena, self.id, "handle was created by another arena");
+
+        self.item(handle.index)
+    }
+
+    /// Attempts to move the `value` into the arena, returning a handle to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena already contains an object for every possible [`Idx`].
+    pub fn try_alloc(&self, value: T) -> Result<Idx<T>, AllocError> {
+        let index = u32::try_from(self.len()).expect("too many objects were allocated");
+        let (block, offset) = locate(index);
+        let items = match self.blocks[block].get() {
+            Some(items) => items,
+            None => {
+                let layout = Layout::array::<T>(block_capacity(block))
+                    .map_err(|_| AllocError::LayoutTooLarge)?;
+                let items = self.arena.try_alloc_with_layout(layout)?.cast::<T>();
+                self.blocks[block].set(Some(items));
+                items
+            }
+        };
+
+        // Safety: offset is within the bounds of the block, and has not been initialized
+        unsafe { items.as_ptr().add(offset).write(value) };
+        self.length.set(self.len() + 1);
+        Ok(self.handle(index))
+    }
+
+    /// Moves the `value` into the arena, returning a handle to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated, or if the arena already contains
+    /// an object for every possible [`Idx`].
+    pub fn alloc(&self, value: T) -> Idx<T> {
+        self.try_alloc(value).unwrap()
+    }
+
+    /// Returns a reference to the object referred to by the `handle`, or [`None`] if it was
+    /// not allocated in this arena.
+    ///
+    /// # Panics
+    ///
+    /// When the `checked-idx` feature is enabled, panics if the `handle` was created by another
+    /// arena.
+    pub fn get(&self, handle: Idx<T>) -> Option<&T> {
+        // Safety: items are initialized, and are only mutated through &mut self
+        self.lookup(handle).map(|item| unsafe { item.as_ref() })
+    }
+
+    /// Returns a mutable reference to the object referred to by the `handle`, or [`None`] if it
+    /// was not allocated in this arena.
+    ///
+    /// # Panics
+    ///
+    /// When the `checked-idx` feature is enabled, panics if the `handle` was created by another
+    /// arena.
+    pub fn get_mut(&mut self, handle: Idx<T>) -> Option<&mut T> {
+        // Safety: items are initialized, and &mut self ensures exclusive access
+        self.lookup(handle).map(|mut item| unsafe { item.as_mut() })
+    }
+
+    /// Returns an iterator over the handles and objects in the arena, in the order that they
+    /// were allocated.
+    ///
+    /// Objects allocated after the iterator is created are not included.
+    pub fn iter(&self) -> IndexArenaIter<'_, T, A> {
+        IndexArenaIter {
+            arena: self,
+            next: 0,
+            end: self.len(),
+        }
+    }
+
+    /// Returns an iterator over the handles and mutable references to the objects in the arena,
+    /// in the order that they were allocated.
+    pub fn iter_mut(&mut self) -> IndexArenaIterMut<'_, T, A> {
+        IndexArenaIterMut {
+            next: 0,
+            end: self.len(),
+            arena: self,
+            _items: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for IndexArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, A: ChunkAllocator> core::ops::Index<Idx<T>> for IndexArena<T, A> {
+    type Output = T;
+
+    fn index(&self, handle: Idx<T>) -> &T {
+        self.get(handle)
+            .expect("handle was not created by this arena")
+    }
+}
+
+impl<T, A: ChunkAllocator> core::ops::IndexMut<Idx<T>> for IndexArena<T, A> {
+    fn index_mut(&mut self, handle: Idx<T>) -> &mut T {
+        self.get_mut(handle)
+            .expect("handle was not created by this arena")
+    }
+}
+
+impl<T, A: ChunkAllocator> Drop for IndexArena<T, A> {
+    fn drop(&mut self) {
+        let length = self.length.replace(0);
+        let mut remaining = length;
+        for (block, items) in self.blocks.iter().enumerate() {
+            let items = match items.get() {
+                Some(items) if remaining > 0 => items,
+                _ => break,
+            };
+
+            let count = remaining.min(block_capacity(block));
+            remaining -= count;
+
+            // Safety: the first count items in the block are initialized, and length was set to
+            // zero so they can no longer be used
+            unsafe {
+                core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+                    items.as_ptr(),
+                    count,
+                ));
+            }
+        }
+    }
+}
+
+impl<T: core::fmt::Debug, A: ChunkAllocator> core::fmt::Debug for IndexArena<T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'t, T, A: ChunkAllocator> IntoIterator for &'t IndexArena<T, A> {
+    type Item = (Idx<T>, &'t T);
+    type IntoIter = IndexArenaIter<'t, T, A>;
+
+    fn into_iter(self) -> IndexArenaIter<'t, T, A> {
+        self.iter()
+    }
+}
+
+impl<'t, T, A: ChunkAllocator> IntoIterator for &'t mut IndexArena<T, A> {
+    type Item = (Idx<T>, &'t mut T);
+    type IntoIter = IndexArenaIterMut<'t, T, A>;
+
+    fn into_iter(self) -> IndexArenaIterMut<'t, T, A> {
+        self.iter_mut()
+    }
+}
+
+// Safety: borrow checker ensures there are no extant references when the arena is sent
+unsafe impl<T: Send, A: ChunkAllocator + Send> Send for IndexArena<T, A> {}
+
+/// An iterator over the handles and objects in an [`IndexArena`].
+///
+/// See [`IndexArena::iter`] for more information.
+pub struct IndexArenaIter<'t, T, A: ChunkAllocator = Global> {
+    arena: &'t IndexArena<T, A>,
+    next: usize,
+    end: usize,
+}
+
+impl<'t, T, A: ChunkAllocator> Iterator for IndexArenaIter<'t, T, A> {
+    type Item = (Idx<T>, &'t T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+
+        let index = self.next as u32;
+        self.next += 1;
+        let item = self.arena.item(index)?;
+
+        // Safety: items are initialized, and are only mutated through &mut self
+        Some((self.arena.handle(index), unsafe { item.as_ref() }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, A: ChunkAllocator> ExactSizeIterator for IndexArenaIter<'_, T, A> {}
+
+impl<T, A: ChunkAllocator> core::iter::FusedIterator for IndexArenaIter<'_, T, A> {}
+
+impl<T, A: ChunkAllocator> Clone for IndexArenaIter<'_, T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            arena: self.arena,
+            next: self.next,
+            end: self.end,
+        }
+    }
+}
+
+impl<T: core::fmt::Debug, A: ChunkAllocator> core::fmt::Debug for IndexArenaIter<'_, T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the handles and mutable references to the objects in an [`IndexArena`].
+///
+/// See [`IndexArena::iter_mut`] for more information.
+pub struct IndexArenaIterMut<'t, T, A: ChunkAllocator = Global> {
+    arena: &'t IndexArena<T, A>,
+    next: usize,
+    end: usize,
+    _items: PhantomData<&'t mut T>,
+}
+
+impl<'t, T, A: ChunkAllocator> Iterator for IndexArenaIterMut<'t, T, A> {
+    type Item = (Idx<T>, &'t mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.end {
+            return None;
+        }
+
+        let index = self.next as u32;
+        self.next += 1;
+        let mut item = self.arena.item(index)?;
+
+        // Safety: iterator has exclusive access to the arena, and each item is only yielded once
+        Some((self.arena.handle(index), unsafe { item.as_mut() }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, A: ChunkAllocator> ExactSizeIterator for IndexArenaIterMut<'_, T, A> {}
+
+impl<T, A: ChunkAllocator> core::iter::FusedIterator for IndexArenaIterMut<'_, T, A> {}
+
+impl<T, A: ChunkAllocator> core::fmt::Debug for IndexArenaIterMut<'_, T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IndexArenaIterMut")
+            .field("remaining", &(self.end - self.next))
+            .finish()
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::{locate, Idx, IndexArena};
+
+    #[test]
+    fn handles_locate_items_in_blocks() {
+        assert_eq!(locate(0), (0, 0));
+        assert_eq!(locate(15), (0, 15));
+        assert_eq!(locate(16), (1, 0));
+        assert_eq!(locate(47), (1, 31));
+        assert_eq!(locate(48), (2, 0));
+        assert_eq!(locate(u32::MAX), (super::BLOCK_COUNT - 1, 15));
+
+        #[cfg(not(feature = "checked-idx"))]
+        assert_eq!(core::mem::size_of::<Idx<u64>>(), 4);
+    }
+
+    #[test]
+    fn objects_are_looked_up_and_dropped() {
+        let counter = alloc::rc::Rc::new(());
+        let mut arena = IndexArena::new();
+        let handles = (0..1000)
+            .map(|i| arena.alloc((i, alloc::rc::Rc::clone(&counter))))
+            .collect::<alloc::vec::Vec<_>>();
+
+        assert_eq!(arena.len(), 1000);
+        assert_eq!(arena[handles[500]].0, 500);
+        arena[handles[999]].0 = -1;
+        assert_eq!(arena.get(handles[999]).map(|item| item.0), Some(-1));
+        assert!(arena
+            .iter()
+            .map(|(handle, _)| handle)
+            .eq(handles.iter().copied()));
+
+        for (_, item) in arena.iter_mut() {
+            item.0 += 1;
+        }
+
+        assert_eq!(arena[handles[0]].0, 1);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1001);
+        core::mem::drop(arena);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+    }
+
+    #[cfg(feature = "checked-idx")]
+    #[test]
+    #[should_panic = "handle was created by another arena"]
+    fn handles_from_other_arenas_are_rejected() {
+        let first = IndexArena::new();
+        let second = IndexArena::new();
+        let handle: Idx<u8> = first.alloc(1);
+        second.alloc(2);
+        let _ = second.get(handle);
+    }
+
+    #[cfg(feature = "checked-idx")]
+    #[test]
+    fn handles_from_other_arenas_are_not_equal() {
+        let first = IndexArena::new();
+        let second = IndexArena::new();
+        let handle: Idx<u8> = first.alloc(1);
+        let other = second.alloc(2);
+        assert_eq!(handle.as_u32(), other.as_u32());
+        assert_ne!(handle, other);
+        assert_ne!(handle.cmp(&other), core::cmp::Ordering::Equal);
+    }
+}
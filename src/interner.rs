@@ -0,0 +1,411 @@
+//! Contains types for interning strings into an arena.
+
+use crate::{Allocator, Bump};
+use alloc::vec::Vec;
+
+/// Maps interned strings to their symbols.
+#[cfg(feature = "std")]
+type SymbolMap<'a> = std::collections::HashMap<&'a str, Symbol>;
+
+/// Maps interned strings to their symbols.
+#[cfg(not(feature = "std"))]
+type SymbolMap<'a> = alloc::collections::BTreeMap<&'a str, Symbol>;
+
+/// A compact identifier for a string stored in an [`Interner`].
+///
+/// Symbols are only meaningful for the interner that created them.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    fn from_index(index: usize) -> Self {
+        Self(u32::try_from(index).expect("too many strings were interned"))
+    }
+
+    /// Returns the index of the interned string, in the order that strings were interned.
+    #[inline(always)]
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    #[inline(always)]
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The lookup table shared by [`Interner`] and [`ConcurrentInterner`].
+#[derive(Debug, Default)]
+struct Table<'a> {
+    symbols: SymbolMap<'a>,
+    strings: Vec<&'a str>,
+}
+
+impl<'a> Table<'a> {
+    fn get(&self, text: &str) -> Option<Symbol> {
+        self.symbols.get(text).copied()
+    }
+
+    fn insert(&mut self, text: &'a str) -> Symbol {
+        let symbol = Symbol::from_index(self.strings.len());
+        self.strings.push(text);
+        self.symbols.insert(text, symbol);
+        symbol
+    }
+
+    fn resolve(&self, symbol: Symbol) -> Option<&'a str> {
+        self.strings.get(symbol.index()).copied()
+    }
+}
+
+/// Stores each unique string once in an arena, identifying them with a [`Symbol`].
+///
+/// Strings are allocated with a [`Bump`] allocator, such as an [`Allocator`] or a
+/// [`ThreadAllocator`](crate::sync::ThreadAllocator), while the table used to look them up is
+/// allocated on the heap. See [`ConcurrentInterner`] for an interner that can be shared between
+/// threads.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::prelude::*;
+/// use bumpercar::Interner;
+///
+/// let mut arena = Arena::new();
+/// let allocator = arena.allocator();
+/// let mut interner = Interner::new_in(&allocator);
+///
+/// let hello = interner.intern("hello");
+/// let world = interner.intern("world");
+/// assert_eq!(interner.intern("hello"), hello);
+/// assert_ne!(hello, world);
+/// assert_eq!(interner.resolve(world), "world");
+/// ```
+pub struct Interner<'a, B: Bump<'a, 'a> + ?Sized = Allocator<'a>> {
+    bump: &'a B,
+    table: Table<'a>,
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> Interner<'a, B> {
+    /// Creates an empty [`Interner`] that allocates strings with the given allocator.
+    pub fn new_in(bump: &'a B) -> Self {
+        Self {
+            bump,
+            table: Table::default(),
+        }
+    }
+
+    /// Returns the [`Symbol`] for the given `text`, copying it into the arena if it has not been
+    /// interned yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the string could not be allocated, or if more than [`u32::MAX`]
+    /// strings were interned.
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        match self.table.get(text) {
+            Some(symbol) => symbol,
+            None => self.table.insert(self.bump.alloc_str(text)),
+        }
+    }
+
+    /// Returns the [`Symbol`] for the given `text` if it was already interned.
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.table.get(text)
+    }
+
+    /// Returns the interned string for the given [`Symbol`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Symbol`] was not created by this [`Interner`].
+    pub fn resolve(&self, symbol: Symbol) -> &'a str {
+        self.try_resolve(symbol)
+            .expect("symbol was not created by this interner")
+    }
+
+    /// Returns the interned string for the given [`Symbol`], or [`None`] if the [`Symbol`] was
+    /// not created by this [`Interner`].
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&'a str> {
+        self.table.resolve(symbol)
+    }
+
+    /// Returns the number of unique strings that were interned.
+    pub fn len(&self) -> usize {
+        self.table.strings.len()
+    }
+
+    /// Returns `true` if no strings were interned.
+    pub fn is_empty(&self) -> bool {
+        self.table.strings.is_empty()
+    }
+
+    /// Returns an iterator over each interned string and its [`Symbol`], in the order that they
+    /// were interned.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Symbol, &'a str)> + '_ {
+        self.table
+            .strings
+            .iter()
+            .enumerate()
+            .map(|(index, text)| (Symbol::from_index(index), *text))
+    }
+}
+
+impl<'a, B: Bump<'a, 'a> + ?Sized> core::fmt::Debug for Interner<'a, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An [`Interner`] that can be shared between threads, storing strings in a
+/// [`SharedArena`](crate::sync::SharedArena).
+///
+/// Looking up strings that were already interned only requires a read lock, so the interner can
+/// be used by many threads at once.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{ConcurrentInterner, sync::SharedArena};
+/// use rayon::prelude::*;
+///
+/// let arena = SharedArena::new();
+/// let interner = ConcurrentInterner::new(&arena);
+/// let symbols = ["a", "b", "a", "c", "b"]
+///     .into_par_iter()
+///     .map(|text| interner.intern(text))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(interner.len(), 3);
+/// assert_eq!(symbols[0], symbols[2]);
+/// assert_eq!(interner.resolve(symbols[3]), "c");
+/// ```
+#[cfg(feature = "sync")]
+#[derive(Debug)]
+pub struct ConcurrentInterner<'a, A: crate::ChunkAllocator + Clone = crate::Global> {
+    arena: &'a crate::sync::SharedArena<A>,
+    table: std::sync::RwLock<Table<'a>>,
+}
+
+#[cfg(feature = "sync")]
+impl<'a, A: crate::ChunkAllocator + Clone> ConcurrentInterner<'a, A> {
+    /// Creates an empty [`ConcurrentInterner`] that allocates strings in the given `arena`.
+    pub fn new(arena: &'a crate::sync::SharedArena<A>) -> Self {
+        Self {
+            arena,
+            table: Default::default(),
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Table<'a>> {
+        self.table.read().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Returns the [`Symbol`] for the given `text`, copying it into the arena if it has not been
+    /// interned yet.
+    ///
+    /// Each call that interns a new string obtains a [`ThreadAllocator`] from the arena, which
+    /// locks the arena's mutex once to obtain it and again to return it. Threads that intern many
+    /// strings should use [`ConcurrentInterner::intern_in`] with their own [`ThreadAllocator`]
+    /// instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the string could not be allocated, or if more than [`u32::MAX`]
+    /// strings were interned.
+    ///
+    /// [`ThreadAllocator`]: crate::sync::ThreadAllocator
+    pub fn intern(&self, text: &str) -> Symbol {
+        match self.get(text) {
+            Some(symbol) => symbol,
+            None => self.intern_in(&self.arena.allocator(), text),
+        }
+    }
+
+    /// Returns the [`Symbol`] for the given `text`, copying it into the arena with the given
+    /// allocator if it has not been interned yet.
+    ///
+    /// The string is copied before the write lock is acquired, so the lock is only held while the
+    /// string is inserted into the table. If another thread interns the same string first, the
+    /// copy is deallocated if it is still the allocator's most recent allocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the string could not be allocated, or if more than [`u32::MAX`]
+    /// strings were interned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bumpercar::{ConcurrentInterner, sync::SharedArena};
+    ///
+    /// let arena = SharedArena::new();
+    /// let interner = ConcurrentInterner::new(&arena);
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         scope.spawn(|| {
+    ///             let allocator = arena.allocator();
+    ///             for text in ["x", "y", "z"] {
+    ///                 interner.intern_in(&allocator, text);
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(interner.len(), 3);
+    /// ```
+    pub fn intern_in<'me, B>(&self, bump: &'me B, text: &str) -> Symbol
+    where
+        B: Bump<'me, 'a> + ?Sized,
+    {
+        if let Some(symbol) = self.get(text) {
+            return symbol;
+        }
+
+        let copy = bump.alloc_str(text);
+        let mut table = self
+            .table
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+
+        // Another thread may have interned the string before the lock was acquired
+        match table.get(text) {
+            Some(symbol) => {
+                core::mem::drop(table);
+                let layout = core::alloc::Layout::for_value::<str>(copy);
+                // Safety: copy was allocated by bump, and is not used afterwards
+                unsafe {
+                    bump.dealloc_last(core::ptr::NonNull::from(copy).cast(), layout);
+                }
+                symbol
+            }
+            None => table.insert(copy),
+        }
+    }
+
+    /// Returns the [`Symbol`] for the given `text` if it was already interned.
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.read().get(text)
+    }
+
+    /// Returns the interned string for the given [`Symbol`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Symbol`] was not created by this [`ConcurrentInterner`].
+    pub fn resolve(&self, symbol: Symbol) -> &'a str {
+        self.try_resolve(symbol)
+            .expect("symbol was not created by this interner")
+    }
+
+    /// Returns the interned string for the given [`Symbol`], or [`None`] if the [`Symbol`] was
+    /// not created by this [`ConcurrentInterner`].
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&'a str> {
+        self.read().resolve(symbol)
+    }
+
+    /// Returns the number of unique strings that were interned.
+    pub fn len(&self) -> usize {
+        self.read().strings.len()
+    }
+
+    /// Returns `true` if no strings were interned.
+    pub fn is_empty(&self) -> bool {
+        self.read().strings.is_empty()
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::Interner;
+    use crate::prelude::*;
+
+    #[test]
+    fn strings_are_stored_once() {
+        let mut arena = Arena::new();
+        let allocator = arena.allocator();
+        let mut interner = Interner::new_in(&allocator);
+
+        let first = interner.intern("identifier");
+        let before = allocator.stats().allocated_bytes;
+        let second = interner.intern(&alloc::string::String::from("identifier"));
+        assert_eq!(first, second);
+        assert_eq!(allocator.stats().allocated_bytes, before);
+
+        let other = interner.intern("other");
+        assert_eq!(other.as_u32(), 1);
+        assert_eq!(interner.get("missing"), None);
+        assert_eq!(interner.try_resolve(other), Some("other"));
+        assert!(interner
+            .iter()
+            .map(|(_, text)| text)
+            .eq(["identifier", "other"]));
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn thread_allocator_interner() {
+        let arena = crate::sync::SharedArena::new();
+        let allocator = arena.allocator();
+        let text = {
+            let mut interner = Interner::new_in(&allocator);
+            let symbol = interner.intern("shared");
+            interner.resolve(symbol)
+        };
+
+        assert_eq!(text, "shared");
+        assert_eq!(allocator.stats().allocated_bytes, text.len());
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn concurrent_symbols_are_unique() {
+        use super::ConcurrentInterner;
+        use alloc::{string::ToString, vec::Vec};
+
+        let arena = crate::sync::SharedArena::new();
+        let interner = ConcurrentInterner::new(&arena);
+        let results = std::thread::scope(|scope| {
+            let threads = (0..8)
+                .map(|thread| {
+                    let interner = &interner;
+                    let arena = &arena;
+                    scope.spawn(move || {
+                        let allocator = arena.allocator();
+                        // Each thread interns an overlapping range of strings
+                        (thread * 25..thread * 25 + 100)
+                            .map(|i| {
+                                let text = i.to_string();
+                                let symbol = if i % 2 == 0 {
+                                    interner.intern(&text)
+                                } else {
+                                    interner.intern_in(&allocator, &text)
+                                };
+                                (text, symbol)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            threads
+                .into_iter()
+                .flat_map(|thread| thread.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(interner.len(), 275);
+        for (text, symbol) in &results {
+            assert_eq!(interner.get(text), Some(*symbol));
+            assert_eq!(interner.resolve(*symbol), text);
+        }
+
+        let mut symbols = results
+            .iter()
+            .map(|(_, symbol)| symbol.as_u32())
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+        assert_eq!(symbols, (0..275).collect::<Vec<_>>());
+    }
+}
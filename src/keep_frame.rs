@@ -0,0 +1,252 @@
+use crate::raw_arena::{KeepState, RawArena, RawArenaState};
+use crate::{ChunkAllocator, CloneIn, Global};
+use core::marker::PhantomData;
+
+/// A [`Frame`](crate::Frame) that can keep some values in its parent allocator, which outlive
+/// the frame.
+///
+/// The frame's own allocations are made in separate chunks from the kept values, and are
+/// deallocated once the frame ends. Kept values live for the lifetime `'p` of the parent's
+/// allocations.
+///
+/// See [`Bump::with_frame_keep`](crate::Bump::with_frame_keep) for more information.
+///
+/// ```compile_fail
+/// use bumpercar::prelude::*;
+///
+/// let mut arena = Arena::new();
+/// let mut allocator = arena.allocator();
+/// allocator.with_frame_keep(|frame| {
+///     // Does not compile, values must be kept to be returned from the frame
+///     frame.alloc(32)
+/// });
+/// ```
+#[derive(Debug)]
+pub struct KeepFrame<'p, 'f, A: ChunkAllocator = Global> {
+    arena: &'f mut RawArena<A>,
+    keep: KeepState,
+    _parent: PhantomData<&'p ()>,
+}
+
+/// Reattaches the frame's chunks once kept values are cloned, even if cloning panics.
+struct Keeping<'k, 'p, 'f, A: ChunkAllocator> {
+    frame: &'k mut KeepFrame<'p, 'f, A>,
+    state: Option<RawArenaState>,
+}
+
+impl<A: ChunkAllocator> Drop for Keeping<'_, '_, '_, A> {
+    fn drop(&mut self) {
+        // Safety: state was returned by detach_keep_frame
+        unsafe {
+            self.frame
+                .arena
+                .attach_keep_frame(&mut self.frame.keep, self.state);
+        }
+    }
+}
+
+impl<'p, 'f, A: ChunkAllocator> KeepFrame<'p, 'f, A> {
+    pub(crate) fn try_in_arena<T, F: FnOnce(&mut KeepFrame<'p, '_, A>) -> T>(
+        arena: &'f mut RawArena<A>,
+        f: F,
+    ) -> Result<T, crate::AllocError> {
+        let keep = arena.begin_keep_frame()?;
+        let mut frame = KeepFrame {
+            arena,
+            keep,
+            _parent: PhantomData,
+        };
+
+        Ok(f(&mut frame))
+    }
+
+    /// Clones a value into the parent allocator, so that it outlives the frame.
+    ///
+    /// The value is cloned with its [`CloneIn`] implementation, so any objects it refers to are
+    /// also cloned into the parent.
+    pub fn keep<T: CloneIn<'p> + ?Sized>(&mut self, value: &T) -> T::Cloned
+    where
+        A: 'p,
+    {
+        // Safety: the frame's chunks are reattached by Keeping before the frame allocates again
+        let state = unsafe { self.arena.detach_keep_frame(&self.keep) };
+        let keeping = Keeping { frame: self, state };
+
+        // Safety: the chunks for kept objects come before the frame's chunks, so they are valid
+        // for the lifetime of the parent's allocations. The allocator is dropped before the
+        // frame's chunks are reattached, and is only lent out by shared reference.
+        let allocator = crate::Allocator::<'p, A>::with_arena(unsafe {
+            &mut *(&mut *keeping.frame.arena as *mut RawArena<A>)
+        });
+
+        value.clone_in(&allocator)
+    }
+
+    /// Returns a snapshot of the underlying arena's memory usage, including the frame's
+    /// allocations and the values kept in the parent.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Returns an iterator describing each of the arena's chunks, from the first chunk to the
+    /// last.
+    ///
+    /// See [`ChunkInfo`](crate::ChunkInfo) for more information.
+    pub fn chunks(&self) -> crate::ArenaChunks<'_> {
+        self.arena.chunk_infos()
+    }
+
+    /// Returns a [`Checkpoint`](crate::Checkpoint) that frees the objects allocated with it once
+    /// it is dropped or rolled back.
+    pub fn checkpoint(&mut self) -> crate::Checkpoint<'_, A> {
+        crate::Checkpoint::in_arena(self.arena)
+    }
+}
+
+impl<A: ChunkAllocator> Drop for KeepFrame<'_, '_, A> {
+    fn drop(&mut self) {
+        // Safety: allocations made by the frame cannot outlive it, and nested frames borrow it
+        // mutably, so they have already ended
+        unsafe {
+            self.arena.end_keep_frame(&self.keep);
+        }
+    }
+}
+
+impl<A: ChunkAllocator> crate::bump::private::Sealed for KeepFrame<'_, '_, A> {
+    #[inline(always)]
+    fn current_finger(&self) -> Option<&core::sync::atomic::AtomicPtr<u8>> {
+        self.arena.current_finger()
+    }
+}
+
+// Safety: 'f is the lifetime of the frame, allocations are freed only once the frame is dropped
+unsafe impl<'p, 'f: 'me, 'me, A: ChunkAllocator> crate::Bump<'me, 'f> for KeepFrame<'p, 'f, A> {
+    type ChunkAllocator = A;
+
+    crate::bump::forward_bump_methods!('me, 'f);
+}
+
+#[cfg(feature = "allocator-api2")]
+// Safety: allocations live for the lifetime of the frame, which outlives the reference
+crate::allocator_api::impl_allocator!([A: ChunkAllocator] KeepFrame<'_, '_, A>);
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+    use crate::CloneIn;
+
+    #[derive(Debug, PartialEq)]
+    struct Node<'a> {
+        name: &'a str,
+        children: &'a [Node<'a>],
+    }
+
+    impl<'p> CloneIn<'p> for Node<'_> {
+        type Cloned = Node<'p>;
+
+        fn clone_in<'me, B: Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> Node<'p> {
+            Node {
+                name: self.name.clone_in(bump),
+                children: self.children.clone_in(bump),
+            }
+        }
+    }
+
+    #[test]
+    fn kept_values_outlive_frame() {
+        let mut arena = crate::ArenaBuilder::new().initial_chunk_size(256).build();
+        let mut allocator = arena.allocator();
+        let before = allocator.stats();
+
+        let kept = allocator.with_frame_keep(|frame| {
+            let scratch = frame.alloc_slice_fill(4096, 0u8);
+            let leaves = frame.alloc_slice_from_iter((0..100).map(|i| Node {
+                name: frame.alloc_str(if i % 2 == 0 { "even" } else { "odd" }),
+                children: &[],
+            }));
+            let root = frame.alloc(Node {
+                name: "root",
+                children: leaves,
+            });
+
+            // Kept values may need more than one chunk
+            let kept = frame.keep(&*root);
+            assert_eq!(scratch.len(), 4096);
+            (kept, frame.keep(&"other"))
+        });
+
+        assert_eq!(kept.0.children.len(), 100);
+        assert!(kept
+            .0
+            .children
+            .iter()
+            .step_by(2)
+            .all(|child| child.name == "even"));
+        assert_eq!(kept.1, "other");
+
+        let stats = allocator.stats();
+        assert!(stats.allocated_bytes > before.allocated_bytes);
+        assert!(stats.allocated_bytes < 4096);
+
+        // Frame's chunks are reused, and do not overwrite kept values
+        allocator.alloc_slice_fill(8192, 0xFFu8);
+        assert!(kept
+            .0
+            .children
+            .iter()
+            .all(|child| child.children.is_empty()));
+        assert_eq!(kept.0.name, "root");
+    }
+
+    #[test]
+    fn nested_keep_frames() {
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        let kept = allocator.with_frame_keep(|outer| {
+            let inner = outer.with_frame_keep(|inner| {
+                let text = inner.alloc_str("nested");
+                inner.with_frame(|frame| {
+                    frame.alloc_slice_fill(2048, 1u8);
+                });
+                inner.keep(&&*text)
+            });
+
+            outer.alloc_slice_fill(2048, 2u8);
+            outer.keep(&inner)
+        });
+
+        assert_eq!(kept, "nested");
+    }
+
+    #[cfg(feature = "std")]
+    impl<'p> CloneIn<'p> for crate::testing::Record {
+        type Cloned = &'p Self;
+
+        fn clone_in<'me, B: crate::Bump<'me, 'p> + ?Sized>(&self, bump: &'me B) -> &'p Self {
+            bump.alloc_with_drop(Self::new(self.0, &self.1))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn keep_frame_drops_kept_objects_later() {
+        use crate::testing::{DropLog, Record};
+
+        let dropped = DropLog::default();
+        let mut arena = Arena::new();
+        let mut allocator = arena.allocator();
+        allocator.with_frame_keep(|frame| {
+            let temporary = frame.alloc_with_drop(Record::new(1, &dropped));
+            let kept = frame.keep(temporary);
+            frame.alloc_with_drop(Record::new(2, &dropped));
+            kept
+        });
+        assert_eq!(*dropped.lock().unwrap(), [2, 1]);
+
+        arena.reset();
+        assert_eq!(*dropped.lock().unwrap(), [2, 1, 1]);
+    }
+}
@@ -10,20 +10,54 @@
 extern crate alloc;
 
 mod allocator;
+#[cfg(feature = "allocator-api2")]
+mod allocator_api;
 mod arena;
+mod arena_ref;
+mod builder;
 mod bump;
+mod checkpoint;
+mod chunk_allocator;
+mod clone_in;
+mod error;
 mod frame;
+mod handle;
+mod index_arena;
+mod interner;
+mod keep_frame;
+mod limit;
 mod private;
 mod raw_arena;
+mod retention;
+mod stats;
+#[cfg(all(any(test, miri), feature = "std"))]
+mod testing;
+mod typed_arena;
 
 pub mod boxed;
+pub mod collections;
 #[cfg(feature = "sync")]
 pub mod sync;
 
 pub use allocator::Allocator;
 pub use arena::Arena;
+pub use arena_ref::ArenaRef;
+pub use builder::{ArenaBuilder, ChunkGrowth};
 pub use bump::Bump;
+pub use checkpoint::Checkpoint;
+pub use chunk_allocator::{ChunkAllocator, Global};
+pub use clone_in::CloneIn;
+pub use error::AllocError;
 pub use frame::Frame;
+pub use handle::Handle;
+pub use index_arena::{Idx, IndexArena, IndexArenaIter, IndexArenaIterMut};
+#[cfg(feature = "sync")]
+pub use interner::ConcurrentInterner;
+pub use interner::{Interner, Symbol};
+pub use keep_frame::KeepFrame;
+pub use retention::RetentionPolicy;
+pub use stats::{ArenaChunks, ArenaStats, ChunkInfo, ChunkState};
+pub use typed_arena::{TypedArena, TypedArenaIter, TypedArenaIterMut};
 
 /// Imports commonly used types for bump allocation.
 pub mod prelude {
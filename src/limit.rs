@@ -0,0 +1,197 @@
+//! Tracks the memory used by an arena's chunks, and enforces memory limits.
+
+use crate::AllocError;
+use core::cell::Cell;
+
+/// A memory limit shared between multiple arenas.
+#[cfg(feature = "sync")]
+#[derive(Debug)]
+pub(crate) struct SharedLimit {
+    limit: usize,
+    used: core::sync::atomic::AtomicUsize,
+}
+
+#[cfg(feature = "sync")]
+impl SharedLimit {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            used: core::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.limit
+            .saturating_sub(self.used.load(core::sync::atomic::Ordering::Relaxed))
+    }
+
+    fn reserve(&self, bytes: usize) -> Result<(), AllocError> {
+        use core::sync::atomic::Ordering;
+
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(bytes).filter(|total| *total <= self.limit)
+            })
+            .map(|_| ())
+            .map_err(|_| AllocError::LimitExceeded)
+    }
+
+    fn release(&self, bytes: usize) {
+        self.used
+            .fetch_sub(bytes, core::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// The maximum number of bytes that an arena's chunks can occupy.
+#[derive(Clone, Debug)]
+pub(crate) enum MemoryLimit {
+    Unlimited,
+    Local(usize),
+    #[cfg(feature = "sync")]
+    Shared(std::sync::Arc<SharedLimit>),
+}
+
+/// Counts the number of bytes occupied by an arena's chunks.
+#[derive(Debug)]
+pub(crate) struct ChunkMemory {
+    used: Cell<usize>,
+    limit: MemoryLimit,
+}
+
+impl ChunkMemory {
+    pub(crate) const fn new(limit: MemoryLimit) -> Self {
+        Self {
+            used: Cell::new(0),
+            limit,
+        }
+    }
+
+    /// The total number of bytes occupied by the chunks.
+    pub(crate) fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    pub(crate) fn limit(&self) -> Option<usize> {
+        match &self.limit {
+            MemoryLimit::Unlimited => None,
+            MemoryLimit::Local(limit) => Some(*limit),
+            #[cfg(feature = "sync")]
+            MemoryLimit::Shared(shared) => Some(shared.limit()),
+        }
+    }
+
+    /// The number of bytes that can be allocated for new chunks without exceeding the limit.
+    pub(crate) fn remaining(&self) -> Option<usize> {
+        match &self.limit {
+            MemoryLimit::Unlimited => None,
+            MemoryLimit::Local(limit) => Some(limit.saturating_sub(self.used.get())),
+            #[cfg(feature = "sync")]
+            MemoryLimit::Shared(shared) => Some(shared.remaining()),
+        }
+    }
+
+    /// Accounts for the allocation of a chunk, failing if the limit would be exceeded.
+    pub(crate) fn reserve(&self, bytes: usize) -> Result<(), AllocError> {
+        let used = self
+            .used
+            .get()
+            .checked_add(bytes)
+            .ok_or(AllocError::LimitExceeded)?;
+
+        match &self.limit {
+            MemoryLimit::Unlimited => (),
+            MemoryLimit::Local(limit) => {
+                if used > *limit {
+                    return Err(AllocError::LimitExceeded);
+                }
+            }
+            #[cfg(feature = "sync")]
+            MemoryLimit::Shared(shared) => shared.reserve(bytes)?,
+        }
+
+        self.used.set(used);
+        Ok(())
+    }
+
+    /// Accounts for the deallocation of a chunk.
+    pub(crate) fn release(&self, bytes: usize) {
+        debug_assert!(bytes <= self.used.get());
+        self.used.set(self.used.get() - bytes);
+
+        #[cfg(feature = "sync")]
+        if let MemoryLimit::Shared(shared) = &self.limit {
+            shared.release(bytes);
+        }
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn memory_limit_is_enforced() {
+        let mut arena = crate::ArenaBuilder::new()
+            .initial_chunk_size(512)
+            .memory_limit(2048)
+            .build();
+
+        let allocator = arena.allocator();
+        while allocator.try_alloc([0u8; 100]).is_ok() {}
+        assert_eq!(
+            allocator.try_alloc([0u8; 100]).err(),
+            Some(crate::AllocError::LimitExceeded)
+        );
+        assert!(arena.remaining_memory().unwrap() < 100 + 64);
+        assert_eq!(arena.memory_limit(), Some(2048));
+    }
+
+    #[test]
+    fn chunks_are_shrunk_to_fit_memory_limit() {
+        let mut fixed = crate::ArenaBuilder::new()
+            .initial_chunk_size(512)
+            .chunk_growth(crate::ChunkGrowth::Fixed)
+            .memory_limit(2048)
+            .build();
+
+        let allocator = fixed.allocator();
+        while allocator.try_alloc([0u8; 16]).is_ok() {}
+        let capacities = fixed
+            .chunks()
+            .map(|chunk| chunk.capacity())
+            .collect::<Vec<_>>();
+        assert_eq!(capacities.len(), 4);
+        assert!(capacities[..3]
+            .iter()
+            .all(|&capacity| capacity == capacities[0]));
+        assert!(capacities[3] < capacities[0]);
+        assert!(fixed.remaining_memory().unwrap() < 16);
+
+        let mut doubling = crate::ArenaBuilder::new()
+            .initial_chunk_size(256)
+            .memory_limit(1024)
+            .build();
+
+        let allocator = doubling.allocator();
+        while allocator.try_alloc([0u8; 16]).is_ok() {}
+        let capacities = doubling
+            .chunks()
+            .map(|chunk| chunk.capacity())
+            .collect::<Vec<_>>();
+        assert_eq!(capacities.len(), 3);
+        assert!(capacities[2] < capacities[1] * 2);
+        assert!(doubling.remaining_memory().unwrap() < 16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn memory_limit_panics_on_infallible_path() {
+        let mut arena = crate::ArenaBuilder::new().memory_limit(1024).build();
+        arena.allocator().alloc_slice_fill(4096, 0u8);
+    }
+}
@@ -1,42 +1,111 @@
-use alloc::alloc;
+use crate::limit::{ChunkMemory, MemoryLimit};
+use crate::stats::{ArenaChunks, ChunkInfo, ChunkState};
+use crate::{AllocError, ArenaStats, ChunkAllocator, ChunkGrowth, Global, RetentionPolicy};
 use core::alloc::Layout;
 use core::cell::Cell;
 use core::mem::MaybeUninit;
 use core::num::NonZeroUsize;
 use core::ptr::NonNull;
+use core::sync::atomic::{AtomicPtr, Ordering};
 
 const HEADER_SIZE: usize = core::mem::size_of::<ChunkHeader>();
 const CHUNK_ALIGNMENT: usize = 16;
 
-const DEFAULT_CAPACITY: NonZeroUsize = {
-    // Safety: not zero
-    unsafe { NonZeroUsize::new_unchecked(1024) }
-};
+const DEFAULT_CAPACITY: usize = 1024;
 
-// Uses a "downward bumping allocator", see https://fitzgeraldnick.com/2019/11/01/always-bump-downwards.html
+/// Determines the capacity of newly allocated chunks.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ChunkPolicy {
+    pub(crate) initial: usize,
+    pub(crate) growth: ChunkGrowth,
+    pub(crate) minimum: usize,
+    pub(crate) maximum: usize,
+}
 
-#[derive(Debug)]
-#[non_exhaustive]
-struct OutOfMemory;
+impl ChunkPolicy {
+    pub(crate) const DEFAULT: Self = Self {
+        initial: DEFAULT_CAPACITY,
+        growth: ChunkGrowth::Doubling,
+        minimum: 1,
+        maximum: usize::MAX,
+    };
 
-impl core::fmt::Display for OutOfMemory {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "out of memory")
+    /// Calculates the capacity of the chunk following a chunk with the given `previous` capacity.
+    fn next_capacity(&self, previous: Option<NonZeroUsize>) -> usize {
+        let capacity = match previous {
+            None => self.initial,
+            Some(previous) => match self.growth {
+                ChunkGrowth::Doubling => previous.get().saturating_mul(2),
+                ChunkGrowth::Factor(factor) => previous.get().saturating_mul(factor.get()),
+                ChunkGrowth::Linear(increment) => previous.get().saturating_add(increment),
+                ChunkGrowth::Fixed => self.initial,
+            },
+        };
+
+        capacity.min(self.maximum).max(self.minimum).max(1)
     }
 }
 
-type Result<T> = core::result::Result<T, OutOfMemory>;
+// Uses a "downward bumping allocator", see https://fitzgeraldnick.com/2019/11/01/always-bump-downwards.html
+
+type Result<T> = core::result::Result<T, AllocError>;
 
 /// Allows for quick deallocation of a portion of a [`RawArena`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) struct RawArenaState {
     chunk: NonNull<ChunkHeader>,
     finger: NonNull<u8>,
+    padding: usize,
+    /// The most recently registered object whose destructor must be run.
+    drops: Option<NonNull<DropEntry>>,
+}
+
+/// Tracks the chunks used by a frame whose allocations can be kept in its parent.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct KeepState {
+    /// The chunk that kept objects are allocated into.
+    keep_chunk: NonNull<ChunkHeader>,
+    /// The first chunk used by the frame, which comes after every chunk containing kept objects.
+    frame_chunk: NonNull<ChunkHeader>,
+    /// The most recently registered object with a destructor before the frame began.
+    parent_drops: Option<NonNull<DropEntry>>,
+    /// The most recently registered object with a destructor that was kept in the parent.
+    kept_drops: Option<NonNull<DropEntry>>,
+}
+
+/// Precedes an object whose destructor is run once it is deallocated, forming an intrusive list
+/// of such objects from the most recently registered to the least.
+#[repr(C)]
+pub(crate) struct DropEntry {
+    /// The entry that was registered before this one.
+    next: Option<NonNull<DropEntry>>,
+    /// Drops the object following this entry.
+    drop: unsafe fn(NonNull<DropEntry>),
+}
+
+#[repr(C)]
+struct DropNode<T> {
+    entry: DropEntry,
+    value: T,
+}
+
+/// Drops the object following the `entry`.
+///
+/// # Safety
+///
+/// The `entry` must be the start of a [`DropNode<T>`] whose value has not been dropped yet.
+unsafe fn drop_node<T>(entry: NonNull<DropEntry>) {
+    // Safety: ensured by caller
+    unsafe {
+        core::ptr::drop_in_place(core::ptr::addr_of_mut!(
+            (*entry.cast::<DropNode<T>>().as_ptr()).value
+        ));
+    }
 }
 
 /// A memory chunk, the header is followed by the chunk's contents.
 #[repr(C)]
-struct ChunkHeader {
+pub(crate) struct ChunkHeader {
     /// Pointer to the previous chunk.
     previous: Cell<Option<NonNull<Self>>>,
     /// Pointer to the next chunk.
@@ -50,10 +119,11 @@ struct ChunkHeader {
     /// [`start`]. If this is equal to [`start`], then the chunk is full.
     ///
     /// [`start`]: Self::start
-    finger: Cell<NonNull<u8>>,
+    finger: Finger,
+    /// The number of bytes in the allocated region of the chunk's contents that were skipped to
+    /// satisfy alignment requirements.
+    padding: Cell<usize>,
     layout: Layout,
-    ///// Counter used to keep track of the amount of free bytes in this chunk and subsequent chunks.
-    //capacity: Cell<usize>,
 }
 
 impl ChunkHeader {
@@ -75,14 +145,46 @@ impl ChunkHeader {
         }
     }
 
-    ///// Returns the remaining number of bytes in this chunk.
-    //#[inline(always)]
-    //pub(crate) fn size(&self) -> usize {
-    //    self.finger.get().as_ptr() as usize - self.start().as_ptr() as usize
-    //}
+    /// Returns the remaining number of bytes in this chunk.
+    #[inline(always)]
+    pub(crate) fn remaining(&self) -> usize {
+        self.finger.get().as_ptr() as usize - self.start().as_ptr() as usize
+    }
 
+    /// Returns the number of bytes in this chunk that are occupied by allocations or padding.
     #[inline(always)]
-    fn fast_alloc_with_layout(&self, layout: Layout) -> Result<NonNull<u8>> {
+    pub(crate) fn used(&self) -> usize {
+        self.end.as_ptr() as usize - self.finger.get().as_ptr() as usize
+    }
+
+    /// Returns the chunk after this one, if any.
+    pub(crate) fn next_chunk(&self) -> Option<&Self> {
+        self.next.get().map(|next| {
+            // Safety: chunks in the list remain valid while this chunk is
+            unsafe { next.as_ref() }
+        })
+    }
+
+    /// Describes this chunk, treating it as empty if it is a retained chunk.
+    pub(crate) fn info(&self, state: ChunkState) -> ChunkInfo {
+        let (used, padding) = match state {
+            ChunkState::Retained => (0, 0),
+            _ => (self.used(), self.padding.get()),
+        };
+
+        ChunkInfo::new(self.start(), self.end, used, padding, state)
+    }
+
+    /// Marks the entire chunk as free.
+    #[inline(always)]
+    fn reset(&self) {
+        self.finger.set(self.end);
+        self.padding.set(0);
+    }
+
+    /// Attempts to allocate within this chunk, returning [`None`] if the chunk is full.
+    #[inline(always)]
+    fn fast_alloc_with_layout(&self, layout: Layout) -> Option<NonNull<u8>> {
         let start = self.start().as_ptr();
         let mut finger = self.finger.get().as_ptr();
 
@@ -91,7 +193,8 @@ impl ChunkHeader {
 
         // This handles ZSTs correctly
         finger = finger.wrapping_sub(layout.size());
-        finger = finger.wrapping_sub(finger as usize % layout.align());
+        let padding = finger as usize % layout.align();
+        finger = finger.wrapping_sub(padding);
 
         if finger >= start {
             debug_assert!(finger <= self.end.as_ptr());
@@ -100,143 +203,67 @@ impl ChunkHeader {
             let finger = unsafe { NonNull::new_unchecked(finger) };
 
             self.finger.set(finger);
-            Ok(finger)
+            self.padding.set(self.padding.get() + padding);
+            Some(finger)
         } else {
-            Err(OutOfMemory)
+            None
         }
     }
 }
 
-fn get_next_or_allocate_chunk(
-    current: &Cell<Option<NonNull<ChunkHeader>>>,
-    default_capacity: Option<NonZeroUsize>,
-    allocation_request: Option<NonZeroUsize>,
-) -> Result<NonNull<ChunkHeader>> {
-    let previous_chunk = current.get();
-    let previous_header = previous_chunk.map(|previous| {
-        // Safety: previous pointer is valid.
-        unsafe { previous.as_ref() }
-    });
-
-    let mut next_chunk = previous_header.and_then(|chunk| chunk.next.get());
-    let mut next_header = next_chunk.map(|next| {
-        // Safety: next pointer is valid.
-        unsafe { next.as_ref() }
-    });
-
-    let mut size: usize;
-    let old_next: Option<&ChunkHeader>;
-    if let Some(next) = next_header {
-        debug_assert_eq!(next.previous.get(), previous_chunk);
-
-        match allocation_request {
-            Some(request) if request < next.capacity() => {
-                // Special case, existing chunk is too small so reallocation must occur.
-
-                size = HEADER_SIZE.checked_add(request.get()).ok_or(OutOfMemory)?;
-
-                // Go to normal allocation path
-                old_next = next.next.get().map(|next| {
-                    // Safety: next pointer is valid
-                    unsafe { next.as_ref() }
-                });
-            }
-            _ => {
-                // In cases where previous "states" are restored, a chunk may have some allocations remaining
-                // This means that the returned chunk has to be set to an empty state
-                next.finger.set(next.end);
-
-                current.set(next_chunk);
-                return Ok(NonNull::from(next));
-            }
-        }
-    } else {
-        // Need to allocate a new chunk
-
-        size = previous_header
-            .map(|chunk| chunk.capacity().get().checked_mul(2).unwrap_or(usize::MAX))
-            .unwrap_or(default_capacity.unwrap_or(DEFAULT_CAPACITY).get())
-            .checked_add(HEADER_SIZE)
-            .ok_or(OutOfMemory)?;
-
-        // If an alloc request was made that is greater than capacity * 2, need to adjust size so new
-        // chunk will contain the request
-        if let Some(request_size) = allocation_request {
-            let content_size = size - HEADER_SIZE; // Does not underflow, >= HEADER_SIZE
-            if content_size < request_size.get() {
-                size = size
-                    .checked_add(request_size.get() - content_size)
-                    .ok_or(OutOfMemory)?;
-            }
-        }
+/// The bump pointer of a chunk.
+///
+/// This is atomic so that a [`Box`](crate::boxed::Box) dropped on another thread can still
+/// reclaim its memory with [`dealloc_at_finger`]. The thread allocating in the chunk acquires
+/// memory released by such a box, and otherwise only its own thread ever moves the finger.
+#[repr(transparent)]
+struct Finger(AtomicPtr<u8>);
 
-        old_next = None;
+impl Finger {
+    #[inline(always)]
+    fn new(pointer: NonNull<u8>) -> Self {
+        Self(AtomicPtr::new(pointer.as_ptr()))
     }
 
-    let rounded_size = size
-        .checked_add(size % CHUNK_ALIGNMENT)
-        .ok_or(OutOfMemory)?;
-
-    let layout = Layout::from_size_align(rounded_size, CHUNK_ALIGNMENT).map_err(|_| OutOfMemory)?;
-
-    let chunk = {
-        let pointer;
-        let end;
-
-        // Safety: layout size is never 0
-        unsafe {
-            // TODO: Choose whether to alloc or realloc
-            if let Some(next) = next_header {
-                pointer = alloc::realloc(
-                    next as *const ChunkHeader as *mut u8,
-                    next.layout,
-                    layout.size(),
-                );
-
-                // Prevents accidental further usage of dangling next pointer
-                #[allow(unused_assignments)]
-                {
-                    next_header = None;
-                    next_chunk = None;
-                }
-            } else {
-                pointer = alloc::alloc(layout);
-            }
-
-            end = NonNull::new(pointer.add(rounded_size)).ok_or(OutOfMemory)?;
-        }
-
-        let header;
-
-        // Safety: layout uses alignment of ChunkHeader, so reference is aligned
-        unsafe {
-            header = NonNull::new(pointer)
-                .ok_or(OutOfMemory)?
-                .cast::<MaybeUninit<ChunkHeader>>()
-                .as_mut();
-        }
-
-        NonNull::from(header.write(ChunkHeader {
-            previous: Cell::new(previous_chunk),
-            next: Cell::new(old_next.map(NonNull::from)),
-            end,
-            finger: Cell::new(end),
-            layout,
-        }))
-    };
-
-    if let Some(previous) = previous_header {
-        debug_assert!(previous.next.get().is_none());
-        previous.next.set(Some(chunk));
+    #[inline(always)]
+    fn get(&self) -> NonNull<u8> {
+        // Safety: finger is never null
+        unsafe { NonNull::new_unchecked(self.0.load(Ordering::Acquire)) }
     }
 
-    if let Some(old_header) = old_next {
-        // Path only taken if a chunk was "reallocated" to fit a large allocation, need to fix a dangling pointer
-        old_header.previous.set(Some(chunk));
+    #[inline(always)]
+    fn set(&self, pointer: NonNull<u8>) {
+        self.0.store(pointer.as_ptr(), Ordering::Relaxed)
     }
+}
 
-    current.set(Some(chunk));
-    Ok(chunk)
+/// Deallocates the object at `pointer` if it is at the `finger` of its chunk, returning `true` if
+/// the memory was reclaimed.
+///
+/// # Safety
+///
+/// The `finger` must belong to a chunk that is still allocated, and the object must have been
+/// allocated with the given `layout`. Objects in other chunks are never at the `finger`, so they
+/// are left unchanged. The `pointer` must not be used after this call.
+#[inline(always)]
+pub(crate) unsafe fn dealloc_at_finger(
+    finger: &AtomicPtr<u8>,
+    pointer: NonNull<u8>,
+    layout: Layout,
+) -> bool {
+    // Safety: allocation is contained in the chunk, so this does not go past the end
+    let reclaimed = unsafe { pointer.as_ptr().add(layout.size()) };
+
+    // Releases the memory of the object to the thread allocating in the chunk, which may be
+    // another thread if the object was sent to this one
+    finger
+        .compare_exchange(
+            pointer.as_ptr(),
+            reclaimed,
+            Ordering::Release,
+            Ordering::Relaxed,
+        )
+        .is_ok()
 }
 
 struct Chunks<'a> {
@@ -258,53 +285,508 @@ impl<'a> Iterator for Chunks<'a> {
     }
 }
 
-/// Low-level bump allocator that uses memory from the global allocator.
+/// Low-level bump allocator that uses memory from a [`ChunkAllocator`].
 ///
 /// Users should ensure that they do not use pointers to allocated objects
 /// after the arena has been dropped.
-pub(crate) struct RawArena {
+pub(crate) struct RawArena<A: ChunkAllocator = Global> {
     current_chunk: Cell<Option<NonNull<ChunkHeader>>>,
+    /// The most recently registered object whose destructor is run when it is deallocated.
+    drops: Cell<Option<NonNull<DropEntry>>>,
+    /// Incremented whenever objects that handles may refer to are deallocated.
+    generation: Cell<u64>,
+    /// Identifies the arena to its handles, assigned when the first handle is created.
+    id: Cell<Option<NonZeroUsize>>,
+    allocator: A,
+    policy: ChunkPolicy,
+    memory: ChunkMemory,
 }
 
-impl RawArena {
-    pub(crate) fn with_capacity(capacity: usize) -> Self {
-        let arena = Self::default();
+impl<A: ChunkAllocator> RawArena<A> {
+    #[inline(always)]
+    pub(crate) const fn new_in(allocator: A) -> Self {
+        Self::with_settings_in(ChunkPolicy::DEFAULT, MemoryLimit::Unlimited, allocator)
+    }
+
+    #[inline(always)]
+    pub(crate) const fn with_settings_in(
+        policy: ChunkPolicy,
+        limit: MemoryLimit,
+        allocator: A,
+    ) -> Self {
+        Self {
+            current_chunk: Cell::new(None),
+            drops: Cell::new(None),
+            generation: Cell::new(0),
+            id: Cell::new(None),
+            allocator,
+            policy,
+            memory: ChunkMemory::new(limit),
+        }
+    }
+
+    pub(crate) fn with_capacity_in(capacity: usize, allocator: A) -> Self {
+        Self::try_with_capacity_in(capacity, allocator).unwrap()
+    }
+
+    pub(crate) fn try_with_capacity_in(capacity: usize, allocator: A) -> Result<Self> {
+        let arena = Self::new_in(allocator);
 
         if let actual_capacity @ Some(_) = NonZeroUsize::new(capacity) {
-            get_next_or_allocate_chunk(&arena.current_chunk, actual_capacity, None).unwrap();
+            arena.get_next_or_allocate_chunk(actual_capacity)?;
         }
 
-        arena
+        Ok(arena)
+    }
+
+    #[inline(always)]
+    pub(crate) fn chunk_allocator(&self) -> &A {
+        &self.allocator
+    }
+
+    #[inline(always)]
+    pub(crate) fn memory(&self) -> &ChunkMemory {
+        &self.memory
+    }
+
+    /// Returns the number of times that objects which handles may refer to were deallocated.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.get()
+    }
+
+    fn invalidate_handles(&self) {
+        self.generation.set(self.generation.get().wrapping_add(1));
+    }
+
+    /// Returns the identifier of the arena used by handles, or [`None`] if no handles were
+    /// created.
+    pub(crate) fn handle_id(&self) -> Option<NonZeroUsize> {
+        self.id.get()
+    }
+
+    /// Returns the arena's identifier and its current generation for a new handle, assigning the
+    /// identifier if this is the first handle.
+    pub(crate) fn register_handle(&self) -> (NonZeroUsize, u64) {
+        static NEXT_ID: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(1);
+
+        let id = match self.id.get() {
+            Some(id) => id,
+            None => {
+                let id = NEXT_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                let id = NonZeroUsize::new(id).expect("too many arenas created handles");
+                self.id.set(Some(id));
+                id
+            }
+        };
+
+        (id, self.generation())
+    }
+
+    fn get_next_or_allocate_chunk(
+        &self,
+        allocation_request: Option<NonZeroUsize>,
+    ) -> Result<NonNull<ChunkHeader>> {
+        let previous_chunk = self.current_chunk.get();
+        let previous_header = previous_chunk.map(|previous| {
+            // Safety: previous pointer is valid.
+            unsafe { previous.as_ref() }
+        });
+
+        let next_header = previous_header
+            .and_then(|chunk| chunk.next.get())
+            .map(|next| {
+                // Safety: next pointer is valid.
+                unsafe { next.as_ref() }
+            });
+
+        // Smallest possible chunk that can contain the request
+        let required_size = HEADER_SIZE
+            .checked_add(allocation_request.map_or(1, NonZeroUsize::get))
+            .ok_or(AllocError::LayoutTooLarge)?;
+
+        let mut size: usize;
+        let replaced: Option<&ChunkHeader>;
+        if let Some(next) = next_header {
+            debug_assert_eq!(next.previous.get(), previous_chunk);
+
+            match allocation_request {
+                Some(request) if next.capacity() < request => {
+                    // Special case, existing chunk is too small so it must be replaced.
+
+                    size = required_size;
+
+                    // Go to normal allocation path
+                    replaced = Some(next);
+                }
+                _ => {
+                    // In cases where previous "states" are restored, a chunk may have some allocations remaining
+                    // This means that the returned chunk has to be set to an empty state
+                    next.reset();
+
+                    self.current_chunk.set(Some(NonNull::from(next)));
+                    return Ok(NonNull::from(next));
+                }
+            }
+        } else {
+            // Need to allocate a new chunk
+
+            size = self
+                .policy
+                .next_capacity(previous_header.map(ChunkHeader::capacity))
+                .checked_add(HEADER_SIZE)
+                .ok_or(AllocError::LayoutTooLarge)?;
+
+            // If an alloc request was made that is greater than the new capacity, need to adjust
+            // size so new chunk will contain the request
+            size = size.max(required_size);
+
+            replaced = None;
+        }
+
+        let round_up = |size: usize| -> Result<usize> {
+            Ok(size
+                .checked_add(CHUNK_ALIGNMENT - 1)
+                .ok_or(AllocError::LayoutTooLarge)?
+                & !(CHUNK_ALIGNMENT - 1))
+        };
+
+        let mut rounded_size = round_up(size)?;
+
+        // Memory of the replaced chunk is reused
+        let replaced_size = replaced.map_or(0, |old| old.layout.size());
+        debug_assert!(replaced_size < rounded_size);
+
+        if let Some(remaining) = self.memory.remaining() {
+            // Try to fit the chunk within the memory limit
+            let available = remaining.saturating_add(replaced_size) & !(CHUNK_ALIGNMENT - 1);
+            if rounded_size > available {
+                // Shrink the chunk to fit, but never below the size needed for the request
+                rounded_size = rounded_size.min(available).max(round_up(required_size)?);
+            }
+        }
+
+        let layout = Layout::from_size_align(rounded_size, CHUNK_ALIGNMENT)
+            .map_err(|_| AllocError::LayoutTooLarge)?;
+
+        self.memory.reserve(rounded_size - replaced_size)?;
+
+        let pointer = match self.allocator.allocate_chunk(layout) {
+            Some(pointer) => pointer,
+            None => {
+                self.memory.release(rounded_size - replaced_size);
+                return Err(AllocError::OutOfMemory);
+            }
+        };
+
+        // Safety: allocation is rounded_size bytes long
+        let end = unsafe { NonNull::new_unchecked(pointer.as_ptr().add(rounded_size)) };
+
+        let old_next = replaced.and_then(|old| old.next.get());
+
+        let chunk = {
+            // Safety: layout uses alignment of ChunkHeader, so reference is aligned
+            let header = unsafe { pointer.cast::<MaybeUninit<ChunkHeader>>().as_mut() };
+
+            NonNull::from(header.write(ChunkHeader {
+                previous: Cell::new(previous_chunk),
+                next: Cell::new(old_next),
+                end,
+                finger: Finger::new(end),
+                padding: Cell::new(0),
+                layout,
+            }))
+        };
+
+        if let Some(previous) = previous_header {
+            debug_assert!(previous.next.get().is_none() || replaced.is_some());
+            previous.next.set(Some(chunk));
+        }
+
+        if let Some(old) = replaced {
+            if let Some(old_next) = old_next {
+                // Safety: next pointer is valid
+                unsafe { old_next.as_ref() }.previous.set(Some(chunk));
+            }
+
+            let old_layout = old.layout;
+
+            // Safety: chunk was allocated with the same layout, and is no longer linked to any others
+            unsafe {
+                self.allocator
+                    .deallocate_chunk(NonNull::from(old).cast(), old_layout)
+            }
+        }
+
+        self.current_chunk.set(Some(chunk));
+        Ok(chunk)
     }
 
     #[inline(always)]
     pub(crate) fn alloc_with_layout(&self, layout: Layout) -> NonNull<u8> {
+        self.try_alloc_with_layout(layout).unwrap()
+    }
+
+    #[inline(always)]
+    pub(crate) fn try_alloc_with_layout(&self, layout: Layout) -> Result<NonNull<u8>> {
         match self.fast_alloc_with_layout(layout) {
-            Ok(allocation) => allocation,
-            Err(_) => self.slow_alloc_with_layout(layout).unwrap(),
+            Some(allocation) => Ok(allocation),
+            None => self.slow_alloc_with_layout(layout),
         }
     }
 
     #[inline(always)]
-    fn fast_alloc_with_layout(&self, layout: Layout) -> Result<NonNull<u8>> {
-        if let Some(chunk) = self.current_chunk.get() {
+    fn fast_alloc_with_layout(&self, layout: Layout) -> Option<NonNull<u8>> {
+        self.current_chunk.get().and_then(|chunk| {
             // Safety: chunk is valid reference
             unsafe { chunk.as_ref() }.fast_alloc_with_layout(layout)
-        } else {
-            Err(OutOfMemory)
-        }
+        })
     }
 
     #[inline(never)]
     fn slow_alloc_with_layout(&self, layout: Layout) -> Result<NonNull<u8>> {
-        let chunk = get_next_or_allocate_chunk(
-            &self.current_chunk,
-            None,
-            NonZeroUsize::new(layout.size()),
-        )?;
+        // Request includes worst case alignment padding, so the new chunk can always contain it
+        let request = layout
+            .size()
+            .checked_add(layout.align() - 1)
+            .ok_or(AllocError::LayoutTooLarge)?;
+
+        let chunk = self.get_next_or_allocate_chunk(NonZeroUsize::new(request))?;
 
         // Safety: chunk is valid reference
-        unsafe { chunk.as_ref() }.fast_alloc_with_layout(layout)
+        let allocation = unsafe { chunk.as_ref() }.fast_alloc_with_layout(layout);
+        debug_assert!(allocation.is_some());
+        allocation.ok_or(AllocError::LayoutTooLarge)
+    }
+
+    /// Returns the current chunk if `pointer` refers to the most recent allocation made in it.
+    #[inline(always)]
+    fn last_allocation_chunk(&self, pointer: NonNull<u8>) -> Option<&ChunkHeader> {
+        self.current_chunk
+            .get()
+            .map(|chunk| {
+                // Safety: chunk is valid reference
+                unsafe { chunk.as_ref() }
+            })
+            .filter(|chunk| chunk.finger.get() == pointer)
+    }
+
+    /// Deallocates the object at `pointer` if it was the most recent allocation, returning `true`
+    /// if the memory was reclaimed.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must refer to an object allocated in this arena with the given `layout`, and
+    /// must not be used after this call.
+    pub(crate) unsafe fn dealloc_last(&self, pointer: NonNull<u8>, layout: Layout) -> bool {
+        self.current_finger().map_or(false, |finger| {
+            // Safety: ensured by caller, objects at the finger are in the current chunk
+            unsafe { dealloc_at_finger(finger, pointer, layout) }
+        })
+    }
+
+    /// Moves the `value` into the arena, registering it so that its destructor is run once it is
+    /// deallocated by a reset, by restoring an earlier state, or by dropping the arena.
+    ///
+    /// # Safety
+    ///
+    /// The `value` must be safe to drop whenever and wherever the arena is reset or dropped.
+    pub(crate) unsafe fn try_alloc_with_drop<T>(&self, value: T) -> Result<NonNull<T>> {
+        if !core::mem::needs_drop::<T>() {
+            let allocation = self.try_alloc_with_layout(Layout::new::<T>())?.cast::<T>();
+            // Safety: allocation is valid for writes
+            unsafe { allocation.as_ptr().write(value) };
+            return Ok(allocation);
+        }
+
+        let node = self
+            .try_alloc_with_layout(Layout::new::<DropNode<T>>())?
+            .cast::<DropNode<T>>();
+
+        // Safety: allocation is valid for writes
+        unsafe {
+            node.as_ptr().write(DropNode {
+                entry: DropEntry {
+                    next: self.drops.get(),
+                    drop: drop_node::<T>,
+                },
+                value,
+            });
+        }
+
+        self.drops.set(Some(node.cast()));
+
+        // Safety: node is not null
+        Ok(unsafe { NonNull::new_unchecked(core::ptr::addr_of_mut!((*node.as_ptr()).value)) })
+    }
+
+    /// Runs the destructors of the registered objects, from the most recent until the `last`
+    /// entry, which is not dropped.
+    ///
+    /// # Safety
+    ///
+    /// The objects must not be used after this call, and `last` must be [`None`] or an entry
+    /// that is still registered.
+    unsafe fn run_drops(&self, last: Option<NonNull<DropEntry>>) {
+        while let Some(entry) = self.drops.get().filter(|entry| Some(*entry) != last) {
+            // Unlink first, so that a panicking destructor is not run again
+            // Safety: entry is still registered, so it is valid
+            let header = unsafe { entry.as_ptr().read() };
+            self.drops.set(header.next);
+
+            // Safety: entry was registered for an object that has not been dropped yet
+            unsafe { (header.drop)(entry) }
+        }
+    }
+
+    /// Returns the finger of the current chunk, which allows the most recent allocation to be
+    /// deallocated later with [`dealloc_at_finger`].
+    pub(crate) fn current_finger(&self) -> Option<&AtomicPtr<u8>> {
+        self.current_chunk.get().map(|chunk| {
+            // Safety: chunk is valid reference
+            &unsafe { chunk.as_ref() }.finger.0
+        })
+    }
+
+    /// Attempts to resize the object at `pointer` within the current chunk, returning a pointer to
+    /// the resized object.
+    ///
+    /// Since the arena bumps downwards, the contents of the object are moved if it is grown.
+    /// Returns [`None`] if the object was not the most recent allocation, or if the current chunk
+    /// does not have enough space remaining.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must refer to an object allocated in this arena with the `old_layout`. If
+    /// [`Some`] is returned, the `pointer` must no longer be used.
+    pub(crate) unsafe fn realloc_last(
+        &self,
+        pointer: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Option<NonNull<u8>> {
+        let chunk = self.last_allocation_chunk(pointer)?;
+        let start = chunk.start().as_ptr() as usize;
+
+        // The end of the object stays in place, which avoids leaving a gap in the chunk
+        let end = pointer.as_ptr() as usize + old_layout.size();
+        let mut address = end.checked_sub(new_layout.size())?;
+        address -= address % new_layout.align();
+
+        if address < start {
+            return None;
+        }
+
+        // Safety: address is within the chunk
+        let new_pointer =
+            unsafe { NonNull::new_unchecked(chunk.start().as_ptr().add(address - start)) };
+
+        // Safety: both regions are within the chunk, and may overlap
+        unsafe {
+            core::ptr::copy(
+                pointer.as_ptr(),
+                new_pointer.as_ptr(),
+                old_layout.size().min(new_layout.size()),
+            );
+        }
+
+        // Gap between the end of the resized object and the old end is padding
+        chunk
+            .padding
+            .set(chunk.padding.get() + (end - address - new_layout.size()));
+        chunk.finger.set(new_pointer);
+        Some(new_pointer)
+    }
+
+    /// Grows the object at `pointer`, in place if it was the most recent allocation, otherwise by
+    /// copying it into a new allocation.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must refer to an object allocated in this arena with the `old_layout`, and
+    /// the `new_layout` must not be smaller. If [`Ok`] is returned, the `pointer` must no longer
+    /// be used.
+    pub(crate) unsafe fn grow(
+        &self,
+        pointer: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>> {
+        debug_assert!(old_layout.size() <= new_layout.size());
+
+        // Safety: ensured by caller
+        if let Some(resized) = unsafe { self.realloc_last(pointer, old_layout, new_layout) } {
+            return Ok(resized);
+        }
+
+        let allocation = self.try_alloc_with_layout(new_layout)?;
+
+        // Safety: new allocation is distinct from the old one, and large enough
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                pointer.as_ptr(),
+                allocation.as_ptr(),
+                old_layout.size(),
+            );
+        }
+
+        Ok(allocation)
+    }
+
+    /// Shrinks the object at `pointer`, reclaiming memory if it was the most recent allocation.
+    ///
+    /// # Safety
+    ///
+    /// The `pointer` must refer to an object allocated in this arena with the `old_layout`, and
+    /// the `new_layout` must not be larger. If [`Ok`] is returned, the `pointer` must no longer
+    /// be used.
+    pub(crate) unsafe fn shrink(
+        &self,
+        pointer: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>> {
+        debug_assert!(new_layout.size() <= old_layout.size());
+
+        // Safety: ensured by caller
+        if let Some(resized) = unsafe { self.realloc_last(pointer, old_layout, new_layout) } {
+            Ok(resized)
+        } else if pointer.as_ptr() as usize % new_layout.align() == 0 {
+            Ok(pointer)
+        } else {
+            let allocation = self.try_alloc_with_layout(new_layout)?;
+
+            // Safety: new allocation is distinct from the old one
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    pointer.as_ptr(),
+                    allocation.as_ptr(),
+                    new_layout.size(),
+                );
+            }
+
+            Ok(allocation)
+        }
+    }
+
+    /// Grows or shrinks the object at `pointer`, depending on the size of the `new_layout`.
+    ///
+    /// # Safety
+    ///
+    /// See [`grow`](Self::grow) and [`shrink`](Self::shrink).
+    pub(crate) unsafe fn realloc(
+        &self,
+        pointer: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>> {
+        // Safety: ensured by caller
+        unsafe {
+            if new_layout.size() >= old_layout.size() {
+                self.grow(pointer, old_layout, new_layout)
+            } else {
+                self.shrink(pointer, old_layout, new_layout)
+            }
+        }
     }
 
     pub(crate) unsafe fn alloc_try_with_layout<R, F>(&self, layout: Layout, f: F) -> R
@@ -331,6 +813,104 @@ impl RawArena {
         result
     }
 
+    /// Restores an earlier state after a frame from
+    /// [`Bump::try_with_frame`](crate::Bump::try_with_frame) fails, unless objects that need to be
+    /// dropped were allocated since then.
+    ///
+    /// References to the deallocated objects may outlive the frame, but they must not be used
+    /// once the memory is reused. Objects that need to be dropped are never rolled back, since
+    /// their destructors would run while such references remain.
+    ///
+    /// # Safety
+    ///
+    /// The `state` must have been returned by [`current_state`](RawArena::current_state), and the
+    /// arena must not be used to allocate again for as long as references to objects allocated
+    /// after the `state` remain.
+    pub(crate) unsafe fn roll_back(&self, state: Option<RawArenaState>) {
+        // Drop entries are stored in chunks, so there are none if there were no chunks
+        if self.drops.get() == state.and_then(|state| state.drops) {
+            // Safety: ensured by caller, no destructors are run
+            unsafe { self.restore_state(state) }
+        }
+    }
+
+    /// Moves on to a new chunk for the allocations of a frame, so that the objects kept in its
+    /// parent are allocated in separate chunks.
+    pub(crate) fn begin_keep_frame(&self) -> Result<KeepState> {
+        let keep_chunk = match self.current_chunk.get() {
+            Some(current) => current,
+            None => self.get_next_or_allocate_chunk(None)?,
+        };
+
+        Ok(KeepState {
+            keep_chunk,
+            frame_chunk: self.get_next_or_allocate_chunk(None)?,
+            parent_drops: self.drops.get(),
+            kept_drops: self.drops.get(),
+        })
+    }
+
+    /// Detaches the chunks used by the frame, so that subsequent allocations go into the chunks
+    /// for kept objects.
+    ///
+    /// # Safety
+    ///
+    /// Must be followed by a call to [`attach_keep_frame`](Self::attach_keep_frame) with the
+    /// returned state before the frame allocates again.
+    pub(crate) unsafe fn detach_keep_frame(&self, keep: &KeepState) -> Option<RawArenaState> {
+        let state = self.current_state();
+        self.drops.set(keep.kept_drops);
+
+        // Safety: frame chunk is valid until the arena is dropped
+        let frame = unsafe { keep.frame_chunk.as_ref() };
+        if let Some(previous) = frame.previous.take() {
+            // Safety: previous pointer is valid
+            unsafe { previous.as_ref() }.next.set(None);
+        }
+
+        self.current_chunk.set(Some(keep.keep_chunk));
+        state
+    }
+
+    /// Reattaches the chunks used by the frame after the chunks for kept objects, and restores
+    /// the frame's state.
+    pub(crate) unsafe fn attach_keep_frame(
+        &self,
+        keep: &mut KeepState,
+        state: Option<RawArenaState>,
+    ) {
+        if let Some(current) = self.current_chunk.get() {
+            keep.keep_chunk = current;
+        }
+
+        // The chunks for kept objects are followed by any chunks that were retained while
+        // allocating them
+        if let Some(last) = self.all_chunks().next() {
+            last.next.set(Some(keep.frame_chunk));
+
+            // Safety: frame chunk is valid until the arena is dropped
+            unsafe { keep.frame_chunk.as_ref() }
+                .previous
+                .set(Some(NonNull::from(last)));
+        }
+
+        keep.kept_drops = self
+            .drops
+            .replace(state.map_or(keep.parent_drops, |state| state.drops));
+
+        // Safety: state was obtained from detach_keep_frame
+        unsafe { self.restore_state(state) }
+    }
+
+    /// Moves back to the chunk containing kept objects once a frame ends, treating the chunks
+    /// used by the frame as retained chunks.
+    pub(crate) unsafe fn end_keep_frame(&self, keep: &KeepState) {
+        // Safety: ensured by caller, the frame's objects are registered after the parent's
+        unsafe { self.run_drops(keep.parent_drops) };
+        self.drops.set(keep.kept_drops);
+        self.current_chunk.set(Some(keep.keep_chunk));
+    }
+
     /// Returns an [`ArenaState`], a snapshot of the state of this arena's chunks.
     pub(crate) fn current_state(&self) -> Option<RawArenaState> {
         self.current_chunk.get().map(|chunk| {
@@ -340,6 +920,8 @@ impl RawArena {
             RawArenaState {
                 chunk,
                 finger: header.finger.get(),
+                padding: header.padding.get(),
+                drops: self.drops.get(),
             }
         })
     }
@@ -375,12 +957,16 @@ impl RawArena {
     /// [`restore_state`]: Self::restore_state
     pub(crate) unsafe fn restore_state(&self, state: Option<RawArenaState>) {
         if let Some(restoring) = state {
+            // Safety: objects registered after the state was obtained are no longer used
+            unsafe { self.run_drops(restoring.drops) };
+
             self.current_chunk.set(Some(restoring.chunk));
 
             // Safety: chunk is valid for self
             let chunk = unsafe { restoring.chunk.as_ref() };
 
             chunk.finger.set(restoring.finger);
+            chunk.padding.set(restoring.padding);
         } else {
             // Safety: requirements for this function are stricter than reset()
             unsafe { self.reset() }
@@ -396,32 +982,180 @@ impl RawArena {
         }
     }
 
-    pub(crate) unsafe fn reset(&self) {
+    /// Returns an iterator over all chunks, including those after the current chunk.
+    fn all_chunks(&self) -> Chunks<'_> {
+        let mut last = self.current_chunk.get();
+        while let Some(next) = last.and_then(|chunk| {
+            // Safety: valid for lifetime of self
+            unsafe { chunk.as_ref() }.next.get()
+        }) {
+            last = Some(next);
+        }
+
+        Chunks {
+            current: last.map(|chunk| {
+                // Safety: valid for lifetime of self
+                unsafe { chunk.as_ref() }
+            }),
+        }
+    }
+
+    /// Returns an iterator describing every chunk, from the first chunk to the last.
+    pub(crate) fn chunk_infos(&self) -> ArenaChunks<'_> {
+        let current = self.chunks().next();
+        ArenaChunks::new(self.chunks().last(), current)
+    }
+
+    /// Returns a snapshot of the arena's memory usage.
+    pub(crate) fn stats(&self) -> ArenaStats {
+        let mut stats = ArenaStats {
+            chunk_count: self.all_chunks().count(),
+            reserved_bytes: self.memory.used(),
+            current_chunk_remaining: self.chunks().next().map_or(0, ChunkHeader::remaining),
+            ..Default::default()
+        };
+
         for header in self.chunks() {
-            header.finger.set(header.end);
+            let padding = header.padding.get();
+            stats.allocated_bytes += header.used() - padding;
+            stats.padding_bytes += padding;
+        }
+
+        stats
+    }
+
+    /// Moves the bump pointer back to the start of the first chunk.
+    ///
+    /// The remaining chunks are reset once they are reused.
+    pub(crate) unsafe fn reset(&self) {
+        // Safety: ensured by caller
+        unsafe { self.run_drops(None) };
+        self.invalidate_handles();
+        self.rewind();
+    }
+
+    /// Moves the bump pointer back to the start of the first chunk, without running any drops.
+    fn rewind(&self) {
+        if let Some(first) = self.chunks().last() {
+            first.reset();
+            self.current_chunk.set(Some(NonNull::from(first)));
+        }
+    }
+
+    /// Resets the arena, freeing the chunks that the `policy` does not retain.
+    pub(crate) unsafe fn reset_and_shrink(&self, policy: RetentionPolicy) {
+        if policy == RetentionPolicy::Coalesce && self.all_chunks().nth(1).is_none() {
+            // Already a single chunk, if any
+            // Safety: ensured by caller
+            return unsafe { self.reset() };
         }
+
+        // Safety: ensured by caller
+        unsafe { self.run_drops(None) };
+        self.invalidate_handles();
+
+        let largest = match policy {
+            RetentionPolicy::KeepLargest => self
+                .all_chunks()
+                .max_by_key(|chunk| chunk.capacity())
+                .map(|chunk| chunk as *const ChunkHeader),
+            _ => None,
+        };
+
+        let coalesced = match policy {
+            RetentionPolicy::Coalesce => {
+                // Chunks after the current one still record how much of them was used
+                let used = self
+                    .all_chunks()
+                    .fold(0usize, |total, chunk| total.saturating_add(chunk.used()));
+
+                // The replacement is allocated first, so the old chunks can be kept on failure
+                let current = self.current_chunk.replace(None);
+                let replacement = self.get_next_or_allocate_chunk(NonZeroUsize::new(used));
+                self.current_chunk.set(current);
+
+                match replacement {
+                    Ok(chunk) => Some(chunk),
+                    Err(_) => return self.rewind(),
+                }
+            }
+            _ => None,
+        };
+
+        let mut budget = match policy {
+            RetentionPolicy::KeepBytes(bytes) => bytes,
+            _ => 0,
+        };
+
+        let mut next = self.chunks().last().map(NonNull::from);
+        let mut first_kept = None;
+        let mut last_kept: Option<NonNull<ChunkHeader>> = None;
+        while let Some(chunk) = next {
+            // Safety: chunk is valid until it is deallocated below
+            let header = unsafe { chunk.as_ref() };
+            next = header.next.get();
+
+            let keep = match policy {
+                RetentionPolicy::KeepAll => true,
+                RetentionPolicy::KeepLargest => largest == Some(header as *const ChunkHeader),
+                RetentionPolicy::KeepBytes(_) => {
+                    let fits = header.layout.size() <= budget;
+                    if fits {
+                        budget -= header.layout.size();
+                    }
+                    fits
+                }
+                RetentionPolicy::FreeAll | RetentionPolicy::Coalesce => false,
+            };
+
+            if keep {
+                header.reset();
+                header.previous.set(last_kept);
+                header.next.set(None);
+                match last_kept {
+                    // Safety: kept chunks are valid
+                    Some(last) => unsafe { last.as_ref() }.next.set(Some(chunk)),
+                    None => first_kept = Some(chunk),
+                }
+                last_kept = Some(chunk);
+            } else {
+                let layout = header.layout;
+                self.memory.release(layout.size());
+
+                // Safety: chunk was allocated with the same layout, and will no longer be linked
+                unsafe { self.allocator.deallocate_chunk(chunk.cast(), layout) }
+            }
+        }
+
+        self.current_chunk.set(first_kept.or(coalesced));
     }
 }
 
-impl Default for RawArena {
+impl<A: ChunkAllocator + Default> Default for RawArena<A> {
     #[inline(always)]
     fn default() -> Self {
-        Self {
-            current_chunk: Cell::new(None),
-        }
+        Self::new_in(A::default())
     }
 }
 
-impl Drop for RawArena {
+impl<A: ChunkAllocator> Drop for RawArena<A> {
     fn drop(&mut self) {
-        for header in self.chunks() {
-            // Safety: pointer to chunk is valid, layout is the same
-            unsafe { alloc::dealloc(header as *const ChunkHeader as *mut u8, header.layout) }
+        // Safety: objects cannot be used after the arena is dropped
+        unsafe { self.run_drops(None) };
+
+        for header in self.all_chunks() {
+            let layout = header.layout;
+            let pointer = NonNull::from(header).cast::<u8>();
+            self.memory.release(layout.size());
+
+            // Safety: pointer to chunk is valid, layout is the same, iterator has already moved
+            // on to the previous chunk
+            unsafe { self.allocator.deallocate_chunk(pointer, layout) }
         }
     }
 }
 
-impl core::fmt::Debug for RawArena {
+impl<A: ChunkAllocator> core::fmt::Debug for RawArena<A> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("RawArena").finish_non_exhaustive()
     }
@@ -430,11 +1164,12 @@ impl core::fmt::Debug for RawArena {
 #[cfg(any(test, miri))]
 mod tests {
     use super::RawArena;
+    use crate::Global;
     use core::alloc::Layout;
 
     #[test]
     fn simple_allocate_and_free() {
-        let arena = RawArena::with_capacity(0);
+        let arena = RawArena::with_capacity_in(0, Global);
         arena.alloc_with_layout(Layout::new::<i64>());
         core::mem::drop(arena);
     }
@@ -0,0 +1,136 @@
+//! Contains the [`RetentionPolicy`] used when resetting arenas.
+
+/// Determines which chunks are kept when an arena is reset with
+/// [`Arena::reset_and_shrink`](crate::Arena::reset_and_shrink).
+///
+/// Chunk sizes are measured in the same way as the
+/// [memory limit](crate::ArenaBuilder::memory_limit), including chunk headers.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{prelude::*, RetentionPolicy};
+///
+/// let mut arena = Arena::new();
+/// let allocator = arena.allocator();
+/// allocator.alloc(5u32);
+/// allocator.alloc_slice_fill(100_000, 0u8);
+/// assert_eq!(arena.stats().chunk_count, 2);
+///
+/// arena.reset_and_shrink(RetentionPolicy::KeepLargest);
+/// assert_eq!(arena.stats().chunk_count, 1);
+/// assert!(arena.stats().current_chunk_remaining >= 100_000);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum RetentionPolicy {
+    /// Every chunk is kept, which is equivalent to [`Arena::reset`](crate::Arena::reset).
+    #[default]
+    KeepAll,
+    /// Only the chunk with the largest capacity is kept.
+    KeepLargest,
+    /// Chunks are kept, starting from the first chunk, as long as their combined size does not
+    /// exceed the given number of bytes. Chunks that would exceed it are freed.
+    KeepBytes(usize),
+    /// Every chunk is freed.
+    FreeAll,
+    /// Every chunk is freed, and replaced with a single chunk that can contain all of the bytes
+    /// that were used in the freed chunks.
+    ///
+    /// Chunks that were used and then left by an exited [frame](crate::Bump::with_frame) are
+    /// also counted, so this approximates the most memory that was in use at once, allowing
+    /// later cycles of similar allocations to fit in a single chunk. The replacement chunk is
+    /// allocated before the other chunks are freed; if it cannot be allocated, every chunk is
+    /// kept as in [`KeepAll`](RetentionPolicy::KeepAll).
+    Coalesce,
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn reset_reuses_first_chunk() {
+        let mut arena = crate::ArenaBuilder::new().initial_chunk_size(256).build();
+        arena.allocator().alloc_slice_fill(200, 0u8);
+        arena.allocator().alloc_slice_fill(4096, 0u8);
+        let first = arena.chunks().next().unwrap();
+
+        arena.reset();
+        let allocator = arena.allocator();
+        let value = allocator.alloc(1u8) as *const u8 as usize;
+        assert!(first.address_range().contains(&value));
+        assert_eq!(allocator.chunks().count(), 2);
+    }
+
+    #[test]
+    fn reset_and_shrink_follows_policy() {
+        use crate::RetentionPolicy;
+
+        fn spiked() -> Arena {
+            let mut arena = crate::ArenaBuilder::new().initial_chunk_size(256).build();
+            let allocator = arena.allocator();
+            allocator.alloc_slice_fill(200, 0u8);
+            allocator.alloc_slice_fill(400, 0u8);
+            allocator.alloc_slice_fill(8192, 0u8);
+            assert_eq!(allocator.chunks().count(), 3);
+            arena
+        }
+
+        let capacities = |arena: &Arena| {
+            arena
+                .chunks()
+                .map(|chunk| chunk.capacity())
+                .collect::<alloc::vec::Vec<_>>()
+        };
+
+        let mut arena = spiked();
+        let before = capacities(&arena);
+        arena.reset_and_shrink(RetentionPolicy::KeepAll);
+        assert_eq!(capacities(&arena), before);
+
+        let mut arena = spiked();
+        arena.reset_and_shrink(RetentionPolicy::KeepLargest);
+        assert_eq!(capacities(&arena), [before[2]]);
+
+        let mut arena = spiked();
+        arena.reset_and_shrink(RetentionPolicy::KeepBytes(1024));
+        assert_eq!(capacities(&arena), before[..2]);
+        assert!(arena.stats().reserved_bytes <= 1024);
+
+        let mut arena = spiked();
+        arena.reset_and_shrink(RetentionPolicy::FreeAll);
+        assert_eq!(arena.stats(), crate::ArenaStats::default());
+        assert_eq!(*arena.allocator().alloc(5u8), 5);
+
+        let mut arena = spiked();
+        arena.reset_and_shrink(RetentionPolicy::Coalesce);
+        let coalesced = capacities(&arena);
+        assert_eq!(coalesced.len(), 1);
+        assert!(coalesced[0] >= 200 + 400 + 8192);
+        assert!(coalesced[0] < before.iter().sum::<usize>());
+        let allocator = arena.allocator();
+        allocator.alloc_slice_fill(200, 0u8);
+        allocator.alloc_slice_fill(400, 0u8);
+        allocator.alloc_slice_fill(8192, 0u8);
+        assert_eq!(allocator.chunks().count(), 1);
+    }
+
+    #[test]
+    fn coalesce_keeps_chunks_on_failure() {
+        let mut arena = crate::ArenaBuilder::new()
+            .initial_chunk_size(256)
+            .memory_limit(12288)
+            .build();
+        let allocator = arena.allocator();
+        allocator.alloc_slice_fill(200, 0u8);
+        allocator.alloc_slice_fill(8192, 0u8);
+        let before = arena.stats();
+
+        arena.reset_and_shrink(crate::RetentionPolicy::Coalesce);
+        let after = arena.stats();
+        assert_eq!(after.chunk_count, before.chunk_count);
+        assert_eq!(after.reserved_bytes, before.reserved_bytes);
+        assert_eq!(after.allocated_bytes, 0);
+    }
+}
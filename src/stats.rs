@@ -0,0 +1,268 @@
+//! Contains types describing an arena's memory usage, such as [`ArenaStats`] and [`ChunkInfo`].
+
+use crate::raw_arena::ChunkHeader;
+use core::ptr::NonNull;
+
+/// A snapshot of the memory used by an arena.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::prelude::*;
+///
+/// let mut arena = Arena::new();
+/// let allocator = arena.allocator();
+/// allocator.alloc(1u8);
+/// allocator.alloc(2u32);
+///
+/// let stats = allocator.stats();
+/// assert_eq!(stats.chunk_count, 1);
+/// assert_eq!(stats.allocated_bytes, 5);
+/// assert_eq!(stats.padding_bytes, 3);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct ArenaStats {
+    /// The number of chunks owned by the arena, including retained chunks that are not in use.
+    pub chunk_count: usize,
+    /// The total number of bytes occupied by the arena's chunks, including chunk headers.
+    pub reserved_bytes: usize,
+    /// The number of bytes handed out by allocations since the arena was last reset.
+    pub allocated_bytes: usize,
+    /// The number of bytes skipped over to satisfy the alignment of allocations since the arena
+    /// was last reset.
+    pub padding_bytes: usize,
+    /// The number of bytes left in the current chunk, before a new chunk has to be used.
+    pub current_chunk_remaining: usize,
+}
+
+impl ArenaStats {
+    /// Adds the statistics of another arena to these statistics.
+    #[cfg(feature = "sync")]
+    pub(crate) fn combine(&mut self, other: &Self) {
+        self.chunk_count += other.chunk_count;
+        self.reserved_bytes += other.reserved_bytes;
+        self.allocated_bytes += other.allocated_bytes;
+        self.padding_bytes += other.padding_bytes;
+        self.current_chunk_remaining += other.current_chunk_remaining;
+    }
+}
+
+/// Indicates where a chunk is relative to the chunk that an arena is currently allocating into.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ChunkState {
+    /// The chunk was filled before the arena moved on to the current chunk.
+    Previous,
+    /// The arena is currently allocating into this chunk.
+    Current,
+    /// The chunk sits after the current chunk, and is kept so that it can be reused by later
+    /// allocations, such as after a [`Frame`](crate::Frame) ends or the arena is reset.
+    Retained,
+}
+
+/// Describes a single chunk owned by an arena.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::{prelude::*, ChunkState};
+///
+/// let mut arena = Arena::new();
+/// let mut allocator = arena.allocator();
+/// allocator.alloc(5u32);
+/// allocator.with_frame(|frame| {
+///     frame.alloc_slice_fill(8192, 0u8);
+/// });
+///
+/// let chunks = arena.chunks().collect::<Vec<_>>();
+/// assert_eq!(chunks.len(), 2);
+/// assert_eq!(chunks[0].state(), ChunkState::Current);
+/// assert_eq!(chunks[0].used(), 4);
+/// assert_eq!(chunks[1].state(), ChunkState::Retained);
+/// assert_eq!(chunks[1].used(), 0);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ChunkInfo {
+    start: NonNull<u8>,
+    end: NonNull<u8>,
+    used: usize,
+    padding: usize,
+    state: ChunkState,
+}
+
+impl ChunkInfo {
+    pub(crate) fn new(
+        start: NonNull<u8>,
+        end: NonNull<u8>,
+        used: usize,
+        padding: usize,
+        state: ChunkState,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            used,
+            padding,
+            state,
+        }
+    }
+
+    /// Returns a pointer to the first byte of the chunk's contents.
+    ///
+    /// The pointer must not be dereferenced, since the contents may be in use.
+    pub fn start(&self) -> *const u8 {
+        self.start.as_ptr()
+    }
+
+    /// Returns a pointer to the byte after the last byte of the chunk's contents.
+    pub fn end(&self) -> *const u8 {
+        self.end.as_ptr()
+    }
+
+    /// Returns the address range of the chunk's contents.
+    pub fn address_range(&self) -> core::ops::Range<usize> {
+        self.start.as_ptr() as usize..self.end.as_ptr() as usize
+    }
+
+    /// Returns the number of bytes that can be stored in the chunk, excluding its header.
+    pub fn capacity(&self) -> usize {
+        self.end.as_ptr() as usize - self.start.as_ptr() as usize
+    }
+
+    /// Returns the number of bytes in the chunk occupied by allocations, including
+    /// [padding](ChunkInfo::padding).
+    ///
+    /// This is always `0` for [retained](ChunkState::Retained) chunks.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Returns the number of bytes in the chunk that were skipped to satisfy the alignment of
+    /// allocations.
+    pub fn padding(&self) -> usize {
+        self.padding
+    }
+
+    /// Returns the number of bytes in the chunk that are not in use.
+    pub fn free(&self) -> usize {
+        self.capacity() - self.used
+    }
+
+    /// Indicates whether the chunk is the current chunk, or comes before or after it.
+    pub fn state(&self) -> ChunkState {
+        self.state
+    }
+}
+
+/// An iterator over the chunks of an arena, from the first chunk to the last.
+///
+/// Returned by [`Arena::chunks()`](crate::Arena::chunks) and similar methods.
+#[derive(Clone)]
+pub struct ArenaChunks<'a> {
+    next: Option<&'a ChunkHeader>,
+    current: Option<&'a ChunkHeader>,
+    state: ChunkState,
+}
+
+impl<'a> ArenaChunks<'a> {
+    pub(crate) fn new(first: Option<&'a ChunkHeader>, current: Option<&'a ChunkHeader>) -> Self {
+        Self {
+            next: first,
+            current,
+            state: ChunkState::Previous,
+        }
+    }
+}
+
+impl Iterator for ArenaChunks<'_> {
+    type Item = ChunkInfo;
+
+    fn next(&mut self) -> Option<ChunkInfo> {
+        let chunk = self.next?;
+        let state = match self.current {
+            Some(current) if core::ptr::eq(current, chunk) => {
+                self.state = ChunkState::Retained;
+                ChunkState::Current
+            }
+            _ => self.state,
+        };
+
+        self.next = chunk.next_chunk();
+        Some(chunk.info(state))
+    }
+}
+
+impl core::iter::FusedIterator for ArenaChunks<'_> {}
+
+impl core::fmt::Debug for ArenaChunks<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn stats_track_allocations_and_reset() {
+        let mut arena = Arena::new();
+        assert_eq!(arena.stats(), crate::ArenaStats::default());
+
+        let mut allocator = arena.allocator();
+        allocator.alloc(1u8);
+        allocator.alloc(2u64);
+        allocator.with_frame(|frame| {
+            frame.alloc_slice_fill(4096, 0u8);
+            let stats = frame.stats();
+            assert_eq!(stats.chunk_count, 2);
+            assert_eq!(stats.allocated_bytes, 1 + 8 + 4096);
+        });
+
+        let stats = allocator.stats();
+        assert_eq!(stats.chunk_count, 2);
+        assert_eq!(stats.allocated_bytes, 9);
+        assert_eq!(stats.padding_bytes, 7);
+        assert!(stats.reserved_bytes > 4096 + 1024);
+        assert!(stats.current_chunk_remaining >= 1024 - 16);
+
+        arena.reset();
+        let stats = arena.stats();
+        assert_eq!(stats.chunk_count, 2);
+        assert_eq!(stats.allocated_bytes + stats.padding_bytes, 0);
+    }
+
+    #[test]
+    fn chunks_are_listed_in_order() {
+        use crate::ChunkState;
+
+        let mut arena = crate::ArenaBuilder::new().initial_chunk_size(256).build();
+        assert_eq!(arena.chunks().count(), 0);
+
+        let mut allocator = arena.allocator();
+        allocator.alloc_slice_fill(200, 0u8);
+        allocator.alloc_slice_fill(200, 0u8);
+        allocator.with_frame(|frame| {
+            frame.alloc_slice_fill(4096, 0u8);
+            assert!(frame.chunks().map(|chunk| chunk.state()).eq([
+                ChunkState::Previous,
+                ChunkState::Previous,
+                ChunkState::Current
+            ]));
+        });
+
+        let chunks = allocator.chunks().collect::<alloc::vec::Vec<_>>();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].state(), ChunkState::Previous);
+        assert_eq!(chunks[1].state(), ChunkState::Current);
+        assert_eq!(chunks[1].used(), 200);
+        assert_eq!(chunks[2].state(), ChunkState::Retained);
+        assert!(chunks[2].capacity() >= 4096);
+        assert_eq!(chunks[2].free(), chunks[2].capacity());
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.used()).sum::<usize>(),
+            allocator.stats().allocated_bytes
+        );
+    }
+}
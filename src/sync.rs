@@ -17,51 +17,116 @@
 //! assert_eq!(*my_nums[2], 3);
 //! ```
 
-use crate::raw_arena::RawArena;
-use std::sync::Mutex;
+use crate::limit::{MemoryLimit, SharedLimit};
+use crate::raw_arena::{ChunkPolicy, RawArena};
+use crate::{ChunkAllocator, Global};
+use core::mem::ManuallyDrop;
+use std::sync::{Arc, Mutex};
 
 /// A collection of [`Arena`](crate::Arena) instances shared between threads.
+///
+/// Each [`Arena`](crate::Arena) allocates its chunks using a clone of the [`ChunkAllocator`]
+/// that the [`SharedArena`] was created with.
 #[derive(Debug)]
-pub struct SharedArena {
-    arenas: Mutex<Vec<RawArena>>,
+pub struct SharedArena<A: ChunkAllocator + Clone = Global> {
+    arenas: Mutex<Vec<RawArena<A>>>,
+    allocator: A,
+    policy: ChunkPolicy,
+    limit: Option<Arc<SharedLimit>>,
 }
 
 /// A bump allocator that allocates objects into a [`SharedArena`].
 #[derive(Debug)]
-pub struct ThreadAllocator<'a> {
-    arena: RawArena,
-    owner: &'a SharedArena,
+pub struct ThreadAllocator<'a, A: ChunkAllocator + Clone = Global> {
+    arena: ManuallyDrop<RawArena<A>>,
+    owner: &'a SharedArena<A>,
 }
 
-// Safety: SharedArena lives for 'a, contains all arenas, and outlives 'me
-unsafe impl<'me, 'a: 'me> crate::Bump<'me, 'a> for ThreadAllocator<'a> {
+impl<A: ChunkAllocator + Clone> crate::bump::private::Sealed for ThreadAllocator<'_, A> {
     #[inline(always)]
-    fn alloc_with_layout(&'me self, layout: core::alloc::Layout) -> core::ptr::NonNull<u8> {
-        self.arena.alloc_with_layout(layout)
+    fn current_finger(&self) -> Option<&core::sync::atomic::AtomicPtr<u8>> {
+        // Allocations outlive the thread allocator, and its arena may then be used by another
+        // thread
+        None
     }
+}
 
-    #[inline(always)]
-    fn with_frame<T, F: FnOnce(&mut crate::Frame) -> T>(&'me mut self, f: F) -> T {
-        crate::Frame::in_arena(&mut self.arena, f)
-    }
+// Safety: SharedArena lives for 'a, contains all arenas, and outlives 'me
+unsafe impl<'me, 'a: 'me, A> crate::Bump<'me, 'a> for ThreadAllocator<'a, A>
+where
+    A: ChunkAllocator + Clone,
+{
+    type ChunkAllocator = A;
 
-    #[inline(always)]
-    unsafe fn alloc_try_with_layout<R, F>(&'me self, layout: core::alloc::Layout, f: F) -> R
-    where
-        R: crate::private::Try,
-        F: FnOnce(core::ptr::NonNull<u8>) -> R,
-    {
-        // Safety: ensured by caller
-        unsafe { self.arena.alloc_try_with_layout(layout, f) }
-    }
+    crate::bump::forward_bump_methods!('me, 'a);
 }
 
+#[cfg(feature = "allocator-api2")]
+// Safety: allocations live for the lifetime of the SharedArena, which outlives the reference
+crate::allocator_api::impl_allocator!([A: ChunkAllocator + Clone] ThreadAllocator<'_, A>);
+
 impl SharedArena {
     /// Creates a new empty [`SharedArena`].
     pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<A: ChunkAllocator + Clone> SharedArena<A> {
+    /// Creates a new empty [`SharedArena`] whose chunks are allocated with the given
+    /// [`ChunkAllocator`].
+    ///
+    /// To configure the size of the arenas' chunks, see [`ArenaBuilder`](crate::ArenaBuilder).
+    pub fn new_in(allocator: A) -> Self {
+        Self::with_settings_in(ChunkPolicy::DEFAULT, None, allocator)
+    }
+
+    pub(crate) fn with_settings_in(
+        policy: ChunkPolicy,
+        memory_limit: Option<usize>,
+        allocator: A,
+    ) -> Self {
         Self {
             arenas: Default::default(),
+            allocator,
+            policy,
+            limit: memory_limit.map(|limit| Arc::new(SharedLimit::new(limit))),
+        }
+    }
+
+    /// Returns the maximum number of bytes that the chunks of all arenas can occupy, or [`None`]
+    /// if there is no limit.
+    ///
+    /// See [`ArenaBuilder::memory_limit`](crate::ArenaBuilder::memory_limit) for more
+    /// information.
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.limit.as_ref().map(|limit| limit.limit())
+    }
+
+    /// Returns the number of bytes that can still be used for new chunks before the memory limit
+    /// is reached, or [`None`] if there is no limit.
+    pub fn remaining_memory(&self) -> Option<usize> {
+        self.limit.as_ref().map(|limit| limit.remaining())
+    }
+
+    /// Returns a reference to the [`ChunkAllocator`] used to allocate the arenas' chunks.
+    pub fn chunk_allocator(&self) -> &A {
+        &self.allocator
+    }
+
+    /// Returns the combined memory usage of the arenas that are not currently in use by a
+    /// [`ThreadAllocator`].
+    ///
+    /// Since [`ThreadAllocator`]s return their arenas when dropped, the statistics cover every
+    /// arena once all of them have been dropped. The
+    /// [`current_chunk_remaining`](crate::ArenaStats::current_chunk_remaining) is the sum for all
+    /// of these arenas.
+    pub fn stats(&self) -> crate::ArenaStats {
+        let mut stats = crate::ArenaStats::default();
+        for arena in self.arenas.lock().unwrap().iter() {
+            stats.combine(&arena.stats());
         }
+        stats
     }
 
     /// Marks the memory used by each [`Arena`](crate::Arena) as being freed.
@@ -76,20 +141,63 @@ impl SharedArena {
         }
     }
 
+    /// Resets each [`Arena`](crate::Arena), and frees the chunks that are not kept by the given
+    /// `policy`.
+    ///
+    /// The `policy` applies to each arena separately. See
+    /// [`RetentionPolicy`](crate::RetentionPolicy) for more information.
+    pub fn reset_and_shrink(&mut self, policy: crate::RetentionPolicy) {
+        for arena in self.arenas.get_mut().unwrap().iter_mut() {
+            // Safety: &mut self ensures no extant references into arena
+            unsafe {
+                arena.reset_and_shrink(policy);
+            }
+        }
+    }
+
     /// Obtains a [`ThreadAllocator`] for use within the current thread.
-    pub fn allocator(&self) -> ThreadAllocator<'_> {
+    pub fn allocator(&self) -> ThreadAllocator<'_, A> {
+        let arena = self.arenas.lock().unwrap().pop();
         ThreadAllocator {
-            arena: self.arenas.lock().unwrap().pop().unwrap_or_default(),
+            arena: ManuallyDrop::new(arena.unwrap_or_else(|| {
+                let limit = match &self.limit {
+                    Some(shared) => MemoryLimit::Shared(shared.clone()),
+                    None => MemoryLimit::Unlimited,
+                };
+
+                RawArena::with_settings_in(self.policy, limit, self.allocator.clone())
+            })),
             owner: self,
         }
     }
 }
 
-impl Drop for ThreadAllocator<'_> {
+impl<A: ChunkAllocator + Clone> ThreadAllocator<'_, A> {
+    /// Returns a snapshot of the memory usage of the arena used by this [`ThreadAllocator`].
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Returns an iterator describing each chunk of the arena used by this [`ThreadAllocator`].
+    ///
+    /// See [`ChunkInfo`](crate::ChunkInfo) for more information.
+    pub fn chunks(&self) -> crate::ArenaChunks<'_> {
+        self.arena.chunk_infos()
+    }
+}
+
+impl<A: ChunkAllocator + Clone> Drop for ThreadAllocator<'_, A> {
     fn drop(&mut self) {
-        // Don't want panic to occur, this will just leak memory if mutex was poisoned
-        if let Ok(mut arenas) = self.owner.arenas.lock() {
-            arenas.push(std::mem::take(&mut self.arena));
+        // Safety: arena is not used after this point
+        let arena = unsafe { ManuallyDrop::take(&mut self.arena) };
+
+        // Don't want panic to occur, this will just leak memory if mutex was poisoned, since
+        // allocations must remain valid for the lifetime of the SharedArena
+        match self.owner.arenas.lock() {
+            Ok(mut arenas) => arenas.push(arena),
+            Err(_) => core::mem::forget(arena),
         }
     }
 }
@@ -102,10 +210,49 @@ impl Default for SharedArena {
 }
 
 // Safety: Safe to share, Mutex guards arenas and only hands them out to one thread at a time
-unsafe impl Sync for SharedArena {}
+unsafe impl<A: ChunkAllocator + Clone + Send + Sync> Sync for SharedArena<A> {}
 
 // Safety: Safe to send across threads, data guarded by Mutex
-unsafe impl Send for SharedArena {}
+unsafe impl<A: ChunkAllocator + Clone + Send> Send for SharedArena<A> {}
 
 // Safety: Borrow checker ensures no dangling pointers if allocator is sent across threads
-unsafe impl Send for ThreadAllocator<'_> {}
+unsafe impl<A: ChunkAllocator + Clone + Send + Sync> Send for ThreadAllocator<'_, A> {}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use crate::{AllocError, ArenaBuilder, Bump};
+
+    #[test]
+    fn memory_limit_is_shared_between_allocators() {
+        let arena = ArenaBuilder::new()
+            .initial_chunk_size(1024)
+            .memory_limit(4096)
+            .build_shared();
+
+        let first = arena.allocator();
+        let second = arena.allocator();
+        first.alloc_slice_fill(3000, 0u8);
+        assert_eq!(
+            second.try_alloc_slice_fill(2000, 0u8).err(),
+            Some(AllocError::LimitExceeded)
+        );
+        assert!(arena.remaining_memory().unwrap() < 2000);
+    }
+
+    #[test]
+    fn stats_cover_returned_arenas() {
+        let arena = crate::sync::SharedArena::new();
+        let first = arena.allocator();
+        let second = arena.allocator();
+        first.alloc(1u32);
+        second.alloc_slice_fill(10, 0u8);
+        assert_eq!(first.stats().allocated_bytes, 4);
+        assert_eq!(arena.stats().chunk_count, 0);
+
+        drop(first);
+        drop(second);
+        let stats = arena.stats();
+        assert_eq!(stats.chunk_count, 2);
+        assert_eq!(stats.allocated_bytes, 14);
+    }
+}
@@ -0,0 +1,23 @@
+//! Contains fixtures shared by the tests of several modules.
+
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+/// Lists the ids of [`Record`]s in the order that they were dropped.
+pub(crate) type DropLog = Arc<Mutex<Vec<u32>>>;
+
+/// An object that adds its id to a [`DropLog`] when it is dropped.
+#[derive(Debug)]
+pub(crate) struct Record(pub(crate) u32, pub(crate) DropLog);
+
+impl Record {
+    pub(crate) fn new(id: u32, log: &DropLog) -> Self {
+        Self(id, Arc::clone(log))
+    }
+}
+
+impl Drop for Record {
+    fn drop(&mut self) {
+        self.1.lock().unwrap().push(self.0);
+    }
+}
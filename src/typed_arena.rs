@@ -0,0 +1,541 @@
+//! Contains an arena for objects of a single type.
+//!
+//! See [`TypedArena`] for more information.
+
+use crate::raw_arena::RawArena;
+use crate::{AllocError, ChunkAllocator, Global};
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// The number of bytes used for the items in the first block of a [`TypedArena`].
+const FIRST_BLOCK_SIZE: usize = 1024;
+
+/// A contiguous run of items in a [`TypedArena`].
+struct Block<T> {
+    next: Cell<Option<NonNull<Block<T>>>>,
+    items: NonNull<T>,
+    length: Cell<usize>,
+    capacity: usize,
+}
+
+impl<T> Block<T> {
+    /// Returns a pointer to the item at the given `index`, which must not exceed the capacity.
+    #[inline(always)]
+    fn item(&self, index: usize) -> *mut T {
+        debug_assert!(index <= self.capacity);
+        // Safety: index is within the bounds of the allocation
+        unsafe { self.items.as_ptr().add(index) }
+    }
+
+    /// Drops every item in the block.
+    ///
+    /// # Safety
+    ///
+    /// The items must not be used after this call.
+    unsafe fn drop_items(&self) {
+        let length = self.length.replace(0);
+
+        // Safety: ensured by caller
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.item(0), length));
+        }
+    }
+
+    /// Drops the items in the `first` block and every block after it, in the order that they
+    /// were allocated.
+    ///
+    /// # Safety
+    ///
+    /// The `first` pointer must point to a valid [`Block<T>`], and the items must not be used
+    /// after this call.
+    unsafe fn drop_blocks(first: NonNull<u8>) {
+        let mut next = Some(first.cast::<Self>());
+        while let Some(block) = next {
+            // Safety: blocks are valid until the arena is reset
+            let block = unsafe { block.as_ref() };
+            next = block.next.get();
+
+            // Safety: ensured by caller
+            unsafe { block.drop_items() }
+        }
+    }
+}
+
+/// Drops the items in a [`TypedArena`].
+///
+/// The guard does not mention the type of the items, so the drop checker does not require
+/// borrows held by the items to outlive the arena. This allows items to refer to each other.
+/// Whether the items themselves may observe dangling borrows when dropped is still checked
+/// through the arena's `PhantomData<T>`.
+struct ItemsGuard {
+    /// Points to the first [`Block`] in the arena.
+    first: Cell<Option<NonNull<u8>>>,
+    drop_blocks: unsafe fn(NonNull<u8>),
+}
+
+impl ItemsGuard {
+    /// Drops the items in the arena, leaving it empty.
+    fn drop_items(&self) {
+        if let Some(first) = self.first.take() {
+            // Safety: blocks are valid until the arena is reset, and were unlinked above so the
+            // items can no longer be used
+            unsafe { (self.drop_blocks)(first) }
+        }
+    }
+}
+
+impl Drop for ItemsGuard {
+    fn drop(&mut self) {
+        self.drop_items();
+    }
+}
+
+/// An arena that only contains objects of type `T`.
+///
+/// Unlike an [`Arena`](crate::Arena), a [`TypedArena`] keeps track of the objects that it
+/// contains, so it can iterate over them in the order that they were allocated, and drops them
+/// once the arena is [reset](TypedArena::reset) or dropped.
+///
+/// Objects are allocated through a shared reference, so the returned references can be held
+/// while allocating more objects. The items are stored in blocks of increasing size within the
+/// arena's chunks, and are never moved.
+///
+/// # Example
+///
+/// ```
+/// use bumpercar::TypedArena;
+///
+/// let arena = TypedArena::new();
+/// let first = arena.alloc(String::from("first"));
+/// let second = arena.alloc(String::from("second"));
+/// assert_eq!(first, "first");
+/// assert_eq!(second, "second");
+/// assert_eq!(arena.len(), 2);
+/// assert!(arena.iter().eq(["first", "second"]));
+/// ```
+///
+/// Objects in the arena can refer to each other, as long as they do not use those references
+/// when they are dropped:
+///
+/// ```
+/// use bumpercar::TypedArena;
+/// use std::cell::Cell;
+///
+/// struct Node<'a> {
+///     next: Cell<Option<&'a Node<'a>>>,
+/// }
+///
+/// let arena = TypedArena::new();
+/// let first = arena.alloc(Node { next: Cell::new(None) });
+/// let second = arena.alloc(Node { next: Cell::new(Some(first)) });
+/// first.next.set(Some(second));
+/// ```
+///
+/// ```compile_fail,E0597
+/// use bumpercar::TypedArena;
+/// use std::cell::Cell;
+///
+/// struct Node<'a> {
+///     next: Cell<Option<&'a Node<'a>>>,
+/// }
+///
+/// impl Drop for Node<'_> {
+///     fn drop(&mut self) {
+///         // Might refer to a node that was already dropped
+///         let _ = self.next.get().map(|next| next.next.get());
+///     }
+/// }
+///
+/// let arena = TypedArena::new();
+/// let first = arena.alloc(Node { next: Cell::new(None) });
+/// let second = arena.alloc(Node { next: Cell::new(Some(first)) });
+/// first.next.set(Some(second));
+/// ```
+pub struct TypedArena<T, A: ChunkAllocator = Global> {
+    // Must be dropped before the arena that contains the items
+    items: ItemsGuard,
+    arena: RawArena<A>,
+    last: Cell<Option<NonNull<Block<T>>>>,
+    length: Cell<usize>,
+    _items: PhantomData<T>,
+}
+
+impl<T> TypedArena<T> {
+    /// Creates an empty [`TypedArena`].
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: ChunkAllocator> TypedArena<T, A> {
+    /// Creates an empty [`TypedArena`] whose chunks are allocated with the given
+    /// [`ChunkAllocator`].
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            items: ItemsGuard {
+                first: Cell::new(None),
+                drop_blocks: Block::<T>::drop_blocks,
+            },
+            arena: RawArena::new_in(allocator),
+            last: Cell::new(None),
+            length: Cell::new(0),
+            _items: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the [`ChunkAllocator`] used to allocate the arena's chunks.
+    pub fn chunk_allocator(&self) -> &A {
+        self.arena.chunk_allocator()
+    }
+
+    /// Returns the number of objects in the arena.
+    pub fn len(&self) -> usize {
+        self.length.get()
+    }
+
+    /// Returns `true` if the arena does not contain any objects.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a snapshot of the arena's memory usage.
+    ///
+    /// See [`ArenaStats`](crate::ArenaStats) for more information.
+    pub fn stats(&self) -> crate::ArenaStats {
+        self.arena.stats()
+    }
+
+    /// Allocates a new block with room for at least one more item.
+    #[inline(never)]
+    fn try_grow(&self) -> Result<&Block<T>, AllocError> {
+        let capacity = if core::mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            match self.last.get() {
+                // Safety: blocks are valid until the arena is reset
+                Some(last) => unsafe { last.as_ref() }.capacity.saturating_mul(2),
+                None => (FIRST_BLOCK_SIZE / core::mem::size_of::<T>()).max(1),
+            }
+        };
+
+        let layout = Layout::array::<T>(capacity).map_err(|_| AllocError::LayoutTooLarge)?;
+        let items = self.arena.try_alloc_with_layout(layout)?.cast::<T>();
+        let block = self
+            .arena
+            .try_alloc_with_layout(Layout::new::<Block<T>>())?
+            .cast::<Block<T>>();
+
+        // Safety: allocation is valid for writes
+        unsafe {
+            block.as_ptr().write(Block {
+                next: Cell::new(None),
+                items,
+                length: Cell::new(0),
+                capacity,
+            });
+        }
+
+        match self.last.replace(Some(block)) {
+            // Safety: blocks are valid until the arena is reset
+            Some(last) => unsafe { last.as_ref() }.next.set(Some(block)),
+            None => self.items.first.set(Some(block.cast())),
+        }
+
+        // Safety: block was initialized above
+        Ok(unsafe { block.as_ref() })
+    }
+
+    /// Attempts to move the `value` into the arena, returning a reference to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AllocError`] if any calls to an underlying memory allocator fail, in which
+    /// case the `value` is dropped.
+    pub fn try_alloc(&self, value: T) -> Result<&T, AllocError> {
+        let last = self.last.get().map(|last| {
+            // Safety: blocks are valid until the arena is reset, which requires &mut self
+            unsafe { last.as_ref() }
+        });
+
+        let block = match last {
+            Some(last) if last.length.get() < last.capacity => last,
+            _ => self.try_grow()?,
+        };
+
+        let index = block.length.get();
+        let item = block.item(index);
+
+        // Safety: item is within the block's capacity, and has not been initialized
+        unsafe { item.write(value) };
+        block.length.set(index + 1);
+        self.length.set(self.length.get() + 1);
+
+        // Safety: item was initialized above, and is never moved
+        Ok(unsafe { &*item })
+    }
+
+    /// Moves the `value` into the arena, returning a reference to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the `value` could not be allocated.
+    pub fn alloc(&self, value: T) -> &T {
+        self.try_alloc(value).unwrap()
+    }
+
+    /// Returns an iterator over the objects in the arena, in the order that they were allocated.
+    ///
+    /// Objects allocated after the iterator is created are not included.
+    pub fn iter(&self) -> TypedArenaIter<'_, T> {
+        TypedArenaIter {
+            raw: RawIter::new(self),
+        }
+    }
+
+    /// Returns an iterator over mutable references to the objects in the arena, in the order
+    /// that they were allocated.
+    pub fn iter_mut(&mut self) -> TypedArenaIterMut<'_, T> {
+        TypedArenaIterMut {
+            raw: RawIter::new(self),
+            _items: PhantomData,
+        }
+    }
+
+    /// Drops every object in the arena, and moves the bump pointer back to the first chunk so
+    /// that its memory can be reused.
+    pub fn reset(&mut self) {
+        self.last.set(None);
+        self.length.set(0);
+        self.items.drop_items();
+
+        // Safety: &mut self ensures there are no extant references that can become dangling
+        unsafe { self.arena.reset() }
+    }
+}
+
+impl<T> Default for TypedArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: core::fmt::Debug, A: ChunkAllocator> core::fmt::Debug for TypedArena<T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'t, T, A: ChunkAllocator> IntoIterator for &'t TypedArena<T, A> {
+    type Item = &'t T;
+    type IntoIter = TypedArenaIter<'t, T>;
+
+    fn into_iter(self) -> TypedArenaIter<'t, T> {
+        self.iter()
+    }
+}
+
+impl<'t, T, A: ChunkAllocator> IntoIterator for &'t mut TypedArena<T, A> {
+    type Item = &'t mut T;
+    type IntoIter = TypedArenaIterMut<'t, T>;
+
+    fn into_iter(self) -> TypedArenaIterMut<'t, T> {
+        self.iter_mut()
+    }
+}
+
+// Safety: borrow checker ensures there are no extant references when the arena is sent
+unsafe impl<T: Send, A: ChunkAllocator + Send> Send for TypedArena<T, A> {}
+
+/// Yields pointers to the items in a [`TypedArena`].
+struct RawIter<'t, T> {
+    block: Option<&'t Block<T>>,
+    index: usize,
+    remaining: usize,
+}
+
+impl<'t, T> RawIter<'t, T> {
+    fn new<A: ChunkAllocator>(arena: &'t TypedArena<T, A>) -> Self {
+        Self {
+            block: arena.items.first.get().map(|block| {
+                // Safety: blocks are valid until the arena is reset, which requires &mut self
+                unsafe { block.cast::<Block<T>>().as_ref() }
+            }),
+            index: 0,
+            remaining: arena.len(),
+        }
+    }
+}
+
+impl<T> Clone for RawIter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            block: self.block,
+            index: self.index,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<T> Iterator for RawIter<'_, T> {
+    type Item = *mut T;
+
+    fn next(&mut self) -> Option<*mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let mut block = self.block?;
+        while self.index == block.length.get() {
+            block = block.next.get().map(|next| {
+                // Safety: blocks are valid until the arena is reset, which requires &mut self
+                unsafe { next.as_ref() }
+            })?;
+            self.block = Some(block);
+            self.index = 0;
+        }
+
+        let item = block.item(self.index);
+        self.index += 1;
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the objects in a [`TypedArena`].
+///
+/// See [`TypedArena::iter`] for more information.
+pub struct TypedArenaIter<'t, T> {
+    raw: RawIter<'t, T>,
+}
+
+impl<'t, T> Iterator for TypedArenaIter<'t, T> {
+    type Item = &'t T;
+
+    fn next(&mut self) -> Option<&'t T> {
+        // Safety: item is within the block's length, so it is initialized
+        self.raw.next().map(|item| unsafe { &*item })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for TypedArenaIter<'_, T> {}
+
+impl<T> core::iter::FusedIterator for TypedArenaIter<'_, T> {}
+
+impl<T> Clone for TypedArenaIter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for TypedArenaIter<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over mutable references to the objects in a [`TypedArena`].
+///
+/// See [`TypedArena::iter_mut`] for more information.
+pub struct TypedArenaIterMut<'t, T> {
+    raw: RawIter<'t, T>,
+    _items: PhantomData<&'t mut T>,
+}
+
+impl<'t, T> Iterator for TypedArenaIterMut<'t, T> {
+    type Item = &'t mut T;
+
+    fn next(&mut self) -> Option<&'t mut T> {
+        self.raw.next().map(|item| {
+            // Safety: item is initialized, the iterator has exclusive access to the arena, and
+            // each item is only yielded once
+            unsafe { &mut *item }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for TypedArenaIterMut<'_, T> {}
+
+impl<T> core::iter::FusedIterator for TypedArenaIterMut<'_, T> {}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for TypedArenaIterMut<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let items = self.raw.clone().map(|item| {
+            // Safety: item is initialized, and no mutable references to it exist while the
+            // iterator is borrowed
+            unsafe { &*item }
+        });
+
+        f.debug_list().entries(items).finish()
+    }
+}
+
+#[cfg(any(test, miri))]
+mod tests {
+    use super::TypedArena;
+
+    #[test]
+    fn items_are_iterated_in_order_across_blocks() {
+        let mut arena = TypedArena::new();
+        let first = arena.alloc(0u64);
+        for i in 1..1000u64 {
+            arena.alloc(i);
+        }
+
+        assert_eq!(*first, 0);
+        assert_eq!(arena.len(), 1000);
+        assert!(arena.iter().copied().eq(0..1000));
+
+        arena.iter_mut().for_each(|item| *item *= 2);
+        assert!(arena.iter().copied().eq((0..1000).map(|i| i * 2)));
+
+        let before = arena.stats().chunk_count;
+        arena.reset();
+        assert!(arena.is_empty());
+        assert_eq!(arena.iter().next(), None);
+        arena.alloc(5);
+        assert_eq!(arena.stats().chunk_count, before);
+    }
+
+    #[test]
+    fn items_are_dropped() {
+        let counter = alloc::rc::Rc::new(());
+        let mut arena = TypedArena::new();
+        for _ in 0..100 {
+            arena.alloc(alloc::rc::Rc::clone(&counter));
+        }
+
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 101);
+        arena.reset();
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+
+        arena.alloc(alloc::rc::Rc::clone(&counter));
+        core::mem::drop(arena);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn zero_sized_items() {
+        let arena = TypedArena::new();
+        for _ in 0..10 {
+            arena.alloc(());
+        }
+
+        assert_eq!(arena.iter().count(), 10);
+    }
+}